@@ -0,0 +1,145 @@
+use std::collections::{BTreeSet, HashMap};
+use stardog_wasm_guest::{export_functions, Aggregate, Args, Error, Term};
+
+/// Sorts `values` and picks the one at `p` (between 0 and 1) of the way
+/// through them, interpolating between the two nearest.
+fn percentile(mut values: Vec<f64>, p: f64) -> Result<f64, Error> {
+    if values.is_empty() {
+        return Err(Error::failed("no values"));
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let rank = p * (values.len() - 1) as f64;
+    let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+
+    Ok(values[below] + (values[above] - values[below]) * (rank - below as f64))
+}
+
+/// The middle of the group's numbers.
+#[derive(Default)]
+struct Median {
+    values: Vec<f64>,
+}
+
+impl Aggregate for Median {
+    const NAME: &'static str = "median";
+    const DESCRIPTION: &'static str = "The middle of the group's numbers.";
+    type Output = Result<f64, Error>;
+
+    fn step(&mut self, args: &Args) -> Result<(), Error> {
+        self.values.push(args.require(0)?);
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Median) {
+        self.values.extend(other.values);
+    }
+
+    fn finalize(self) -> Result<f64, Error> {
+        percentile(self.values, 0.5)
+    }
+}
+
+/// The `p`th percentile of the group's numbers, with `p` between 0 and 1.
+#[derive(Default)]
+struct Percentile {
+    values: Vec<f64>,
+    p: Option<f64>,
+}
+
+impl Aggregate for Percentile {
+    const NAME: &'static str = "percentile";
+    const DESCRIPTION: &'static str = "The pth percentile of the group's numbers, with p between 0 and 1.";
+    type Output = Result<f64, Error>;
+
+    fn step(&mut self, args: &Args) -> Result<(), Error> {
+        let p: f64 = args.require(1)?;
+        if !(0.0..=1.0).contains(&p) {
+            return Err(Error::failed(format!("percentile {} is not between 0 and 1", p)));
+        }
+
+        self.values.push(args.require(0)?);
+        self.p = Some(p);
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Percentile) {
+        self.values.extend(other.values);
+        self.p = self.p.or(other.p);
+    }
+
+    fn finalize(self) -> Result<f64, Error> {
+        percentile(self.values, self.p.unwrap_or(0.5))
+    }
+}
+
+/// The group's most common value, the first of them to be seen on a tie.
+#[derive(Default)]
+struct Mode {
+    /// How often each value was seen, and in which order they were first seen.
+    counts: HashMap<Term<'static>, (usize, usize)>,
+}
+
+impl Aggregate for Mode {
+    const NAME: &'static str = "mode";
+    const DESCRIPTION: &'static str = "The group's most common value.";
+    type Output = Result<Term<'static>, Error>;
+
+    fn step(&mut self, args: &Args) -> Result<(), Error> {
+        let value: &Term = args.require(0)?;
+        let seen = self.counts.len();
+        self.counts.entry(value.clone().into_owned()).or_insert((0, seen)).0 += 1;
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Mode) {
+        let mut counts: Vec<_> = other.counts.into_iter().collect();
+        counts.sort_by_key(|(_, (_, order))| *order);
+
+        for (value, (count, _)) in counts {
+            let seen = self.counts.len();
+            self.counts.entry(value).or_insert((0, seen)).0 += count;
+        }
+    }
+
+    fn finalize(self) -> Result<Term<'static>, Error> {
+        self.counts
+            .into_iter()
+            .max_by(|(_, (a, a_order)), (_, (b, b_order))| a.cmp(b).then(b_order.cmp(a_order)))
+            .map(|(value, _)| value)
+            .ok_or_else(|| Error::failed("no values"))
+    }
+}
+
+/// The group's distinct strings in order, joined with the separator.
+#[derive(Default)]
+struct JoinDistinct {
+    values: BTreeSet<String>,
+    separator: Option<String>,
+}
+
+impl Aggregate for JoinDistinct {
+    const NAME: &'static str = "string_join_distinct";
+    const DESCRIPTION: &'static str = "The group's distinct strings in order, joined with the separator.";
+    type Output = String;
+
+    fn step(&mut self, args: &Args) -> Result<(), Error> {
+        self.values.insert(args.require(0)?);
+        if self.separator.is_none() {
+            self.separator = args.argument(1);
+        }
+        Ok(())
+    }
+
+    fn merge(&mut self, other: JoinDistinct) {
+        self.values.extend(other.values);
+        self.separator = self.separator.take().or(other.separator);
+    }
+
+    fn finalize(self) -> String {
+        let values: Vec<_> = self.values.into_iter().collect();
+        values.join(self.separator.as_deref().unwrap_or(" "))
+    }
+}
+
+export_functions!(; aggregates = Median, Percentile, Mode, JoinDistinct);
@@ -0,0 +1,327 @@
+//! Procedural macros for `stardog-wasm-guest`. Use them through the
+//! re-exports in that crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::{ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, parse_macro_input, Attribute, Data, DeriveInput, Error, Expr, Fields, FnArg, Ident, ItemFn, Lit, LitStr, Member, Meta, Pat, ReturnType, Token, Type};
+
+/// Makes a plain Rust function callable through `wasm:call` once it is listed
+/// in the module's `export_functions!`.
+///
+/// Each parameter is converted from the matching `wasm:call` argument with
+/// `FromArgument` and the return value is encoded with `IntoResults`. A missing
+/// or unconvertible argument is returned to the host as an `Error` rather than
+/// trapping, as is the `Err` of a function returning `Result`. `Option`
+/// parameters may be left out, and a `Vec` as the last parameter takes all the
+/// remaining arguments.
+///
+/// A parameter may restrict the terms it takes with an `#[accept(...)]`
+/// attribute, one of `any`, `iri`, `triple`, `plain_literal`, `literal`,
+/// `numeric` or `datatype("xsd:date", ...)`; see `Accept`. Other terms are
+/// returned as type errors without calling the function.
+///
+/// The function's doc comment and signature become its entry in the module's
+/// `describe` output. Functions are assumed to be deterministic; mark ones
+/// that are not with `#[stardog_function(nondeterministic)]`.
+#[proc_macro_attribute]
+pub fn stardog_function(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut function = parse_macro_input!(item as ItemFn);
+    let export = Options::parse(attr.into()).and_then(|options| expand(&function, &options));
+
+    // `#[accept]` is only meaningful to this macro
+    for input in &mut function.sig.inputs {
+        if let FnArg::Typed(typed) = input {
+            typed.attrs.retain(|attr| !attr.path().is_ident("accept"));
+        }
+    }
+
+    match export {
+        Ok(export) => quote!(#function #export).into(),
+        Err(error) => {
+            let error = error.to_compile_error();
+            quote!(#function #error).into()
+        }
+    }
+}
+
+struct Options {
+    deterministic: bool,
+}
+
+impl Options {
+    fn parse(attr: TokenStream2) -> syn::Result<Options> {
+        let mut options = Options { deterministic: true };
+
+        for ident in Punctuated::<Ident, Token![,]>::parse_terminated.parse2(attr)? {
+            if ident == "nondeterministic" {
+                options.deterministic = false;
+            } else {
+                return Err(Error::new_spanned(ident, "unknown #[stardog_function] option"));
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn expand(function: &ItemFn, options: &Options) -> syn::Result<TokenStream2> {
+    let signature = &function.sig;
+
+    if let Some(asyncness) = &signature.asyncness {
+        return Err(Error::new_spanned(asyncness, "#[stardog_function] cannot be async"));
+    }
+    if !signature.generics.params.is_empty() {
+        return Err(Error::new_spanned(&signature.generics, "#[stardog_function] cannot be generic"));
+    }
+    let output = match &signature.output {
+        ReturnType::Type(_, output) => output,
+        ReturnType::Default => return Err(Error::new_spanned(signature, "#[stardog_function] must return a value")),
+    };
+
+    let name = &signature.ident;
+    let mut bindings = Vec::new();
+    let mut names = Vec::new();
+    let mut parameters = Vec::new();
+
+    for (index, input) in signature.inputs.iter().enumerate() {
+        let typed = match input {
+            FnArg::Typed(typed) => typed,
+            FnArg::Receiver(receiver) => {
+                return Err(Error::new_spanned(receiver, "#[stardog_function] cannot take self"));
+            }
+        };
+        let ident = match &*typed.pat {
+            Pat::Ident(pat) => &pat.ident,
+            pat => return Err(Error::new_spanned(pat, "expected a plain parameter name")),
+        };
+        let ty = &typed.ty;
+        let ident_string = ident.to_string();
+        let accept = accept(&typed.attrs)?;
+
+        bindings.push(quote! {
+            let #ident: #ty = args.require_accepted(#index, &#accept)?;
+        });
+        parameters.push(quote! {
+            ::stardog_wasm_guest::Parameter {
+                optional: <#ty as ::stardog_wasm_guest::FromArgument<'_>>::OPTIONAL,
+                variadic: <#ty as ::stardog_wasm_guest::FromArgument<'_>>::VARIADIC,
+                ..::stardog_wasm_guest::Parameter::new(#ident_string, <#ty as ::stardog_wasm_guest::FromArgument<'_>>::TYPE)
+            }
+        });
+        names.push(ident);
+    }
+
+    let vis = &function.vis;
+    let name_string = name.to_string();
+    let description = doc_comment(function);
+    let deterministic = options.deterministic;
+
+    Ok(quote! {
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        #vis struct #name {}
+
+        impl ::stardog_wasm_guest::Function for #name {
+            const NAME: &'static str = #name_string;
+
+            fn call(args: &::stardog_wasm_guest::Args) -> ::std::result::Result<::stardog_wasm_guest::SelectResults, ::stardog_wasm_guest::Error> {
+                #(#bindings)*
+                ::stardog_wasm_guest::IntoResults::into_results(#name(#(#names),*))
+            }
+
+            fn describe() -> ::stardog_wasm_guest::FunctionDescription {
+                ::stardog_wasm_guest::FunctionDescription {
+                    name: #name_string.to_owned(),
+                    description: #description.to_owned(),
+                    deterministic: #deterministic,
+                    parameters: vec![#(#parameters),*],
+                    returns: <#output as ::stardog_wasm_guest::IntoResults>::TYPE.to_owned(),
+                }
+            }
+        }
+    })
+}
+
+/// Lets a struct with one field, like `struct Celsius(f64)`, be a parameter
+/// that takes whatever the field takes.
+#[proc_macro_derive(FromArgument)]
+pub fn derive_from_argument(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let expanded = only_field(&input).and_then(|(member, ty)| {
+        if !input.generics.params.is_empty() {
+            return Err(Error::new_spanned(&input.generics, "#[derive(FromArgument)] cannot be generic"));
+        }
+        let name = &input.ident;
+        let field = quote!(<#ty as ::stardog_wasm_guest::FromArgument<'a>>);
+
+        Ok(quote! {
+            impl<'a> ::stardog_wasm_guest::FromArgument<'a> for #name {
+                const TYPE: &'static str = #field::TYPE;
+                const OPTIONAL: bool = #field::OPTIONAL;
+                const VARIADIC: bool = #field::VARIADIC;
+
+                fn from_argument(term: &'a ::stardog_wasm_guest::Term<'a>) -> ::std::option::Option<Self> {
+                    #field::from_argument(term).map(|value| #name { #member: value })
+                }
+
+                fn from_arguments(
+                    args: &'a ::stardog_wasm_guest::Args<'_>,
+                    index: usize,
+                    accept: &::stardog_wasm_guest::Accept,
+                ) -> ::std::result::Result<Self, ::stardog_wasm_guest::Error> {
+                    #field::from_arguments(args, index, accept).map(|value| #name { #member: value })
+                }
+            }
+        })
+    });
+
+    expanded.unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Lets a struct with one field be returned as whatever the field would be.
+#[proc_macro_derive(IntoResult)]
+pub fn derive_into_result(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let expanded = only_field(&input).map(|(member, ty)| {
+        let name = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics ::stardog_wasm_guest::IntoResult for #name #ty_generics #where_clause {
+                const TYPE: &'static str = <#ty as ::stardog_wasm_guest::IntoResult>::TYPE;
+
+                fn into_result(self) -> ::std::result::Result<::stardog_wasm_guest::Term<'static>, ::stardog_wasm_guest::Error> {
+                    ::stardog_wasm_guest::IntoResult::into_result(self.#member)
+                }
+            }
+        }
+    });
+
+    expanded.unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Makes a struct a result row with one value per field, in order, each
+/// converted with `IntoValue`.
+#[proc_macro_derive(IntoRow)]
+pub fn derive_into_row(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let expanded = fields(&input).map(|fields| {
+        let name = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        let width = fields.len();
+        let kind = match fields.as_slice() {
+            [(_, ty)] => quote!(<#ty as ::stardog_wasm_guest::IntoValue>::TYPE),
+            _ => quote!(::stardog_wasm_guest::ARRAY),
+        };
+        let members = fields.iter().map(|(member, _)| member);
+
+        quote! {
+            impl #impl_generics ::stardog_wasm_guest::IntoRow for #name #ty_generics #where_clause {
+                const WIDTH: usize = #width;
+                const TYPE: &'static str = #kind;
+
+                fn into_row(
+                    self,
+                ) -> ::std::result::Result<::std::vec::Vec<::std::option::Option<::stardog_wasm_guest::Term<'static>>>, ::stardog_wasm_guest::Error> {
+                    ::std::result::Result::Ok(::std::vec![#(::stardog_wasm_guest::IntoValue::into_value(self.#members)?),*])
+                }
+            }
+        }
+    });
+
+    expanded.unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// The fields of a struct with at least one, as they are accessed and their
+/// types.
+fn fields(input: &DeriveInput) -> syn::Result<Vec<(Member, &Type)>> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new_spanned(&input.ident, "only structs can be derived")),
+    };
+    if let Fields::Unit = fields {
+        return Err(Error::new_spanned(&input.ident, "expected a struct with fields"));
+    }
+
+    Ok(fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match &field.ident {
+            Some(ident) => (Member::Named(ident.clone()), &field.ty),
+            None => (Member::Unnamed(index.into()), &field.ty),
+        })
+        .collect())
+}
+
+/// The field of a struct with exactly one.
+fn only_field(input: &DeriveInput) -> syn::Result<(Member, &Type)> {
+    let mut fields = fields(input)?;
+    if fields.len() != 1 {
+        return Err(Error::new_spanned(&input.ident, "expected a struct with exactly one field"));
+    }
+
+    Ok(fields.remove(0))
+}
+
+/// The `Accept` rule of a parameter's `#[accept(...)]` attribute, or
+/// `Accept::Any` without one.
+fn accept(attrs: &[Attribute]) -> syn::Result<TokenStream2> {
+    let attrs: Vec<_> = attrs.iter().filter(|attr| attr.path().is_ident("accept")).collect();
+    let attr = match attrs.as_slice() {
+        [] => return Ok(quote!(::stardog_wasm_guest::Accept::Any)),
+        [attr] => attr,
+        [_, attr, ..] => return Err(Error::new_spanned(attr, "a parameter takes one #[accept] attribute")),
+    };
+
+    attr.parse_args_with(|input: ParseStream| {
+        let rule: Ident = input.parse()?;
+        let accept = match rule.to_string().as_str() {
+            "any" => quote!(Any),
+            "iri" => quote!(Iri),
+            "triple" => quote!(Triple),
+            "plain_literal" => quote!(PlainLiteral),
+            "literal" => quote!(Literal),
+            "numeric" => quote!(Numeric),
+            "datatype" => {
+                let content;
+                parenthesized!(content in input);
+                let datatypes = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                if datatypes.is_empty() {
+                    return Err(Error::new_spanned(rule, "expected at least one datatype"));
+                }
+                let datatypes = datatypes.iter().map(|datatype| match datatype.value().strip_prefix("xsd:") {
+                    Some(local) => format!("http://www.w3.org/2001/XMLSchema#{}", local),
+                    None => datatype.value(),
+                });
+                quote!(Datatypes(&[#(#datatypes),*]))
+            }
+            _ => return Err(Error::new_spanned(rule, "expected any, iri, triple, plain_literal, literal, numeric or datatype(...)")),
+        };
+
+        Ok(quote!(::stardog_wasm_guest::Accept::#accept))
+    })
+}
+
+/// The function's `///` comment, one line per attribute.
+fn doc_comment(function: &ItemFn) -> String {
+    let lines: Vec<String> = function
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(text) => Some(text.value().trim().to_owned()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    lines.join("\n").trim().to_owned()
+}
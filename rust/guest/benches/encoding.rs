@@ -0,0 +1,91 @@
+//! Compares a call through SPARQL JSON with the same call through the binary
+//! encoding, for the one or two short strings a typical function takes.
+//!
+//! Run with `cargo bench -p stardog-wasm-guest`. Timings are of the native
+//! build, so they show the relative cost of the encodings rather than what a
+//! wasm runtime would measure.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use stardog_wasm_guest::{binary, export_functions, free_result, stardog_function, Binding, SelectResults, Term};
+
+extern "C" {
+    #[link_name = "evaluate"]
+    fn evaluate(input: *const u8, input_len: usize, output_len: *mut usize) -> *mut u8;
+    #[link_name = "evaluate_binary"]
+    fn evaluate_binary(input: *const u8, input_len: usize, output_len: *mut usize) -> *mut u8;
+}
+
+#[stardog_function]
+fn to_upper(value: &str) -> String {
+    value.to_uppercase()
+}
+
+#[stardog_function]
+fn concat(a: &str, b: &str) -> String {
+    format!("{}{}", a, b)
+}
+
+export_functions!(to_upper, concat);
+
+const ITERATIONS: u32 = 200_000;
+
+/// The arguments as the host binds them, `value[0]` first.
+fn arguments(function: &'static str, strings: &[&'static str]) -> Vec<Option<Term<'static>>> {
+    let mut values = vec![Some(Term::iri("file:///strings.wasm")), Some(Term::literal(function))];
+    values.extend(strings.iter().map(|string| Some(Term::literal(*string))));
+
+    values
+}
+
+/// What `Call.java` writes: one row per argument.
+fn json(values: &[Option<Term<'static>>]) -> Vec<u8> {
+    let vars: Vec<String> = (0..values.len()).map(|i| format!("value[{}]", i)).collect();
+    let mut document = SelectResults::new(vars.clone());
+    for (var, value) in vars.into_iter().zip(values) {
+        document.push(Binding::new().with(var, value.clone().unwrap()));
+    }
+
+    serde_json::to_vec(&document).unwrap()
+}
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+
+    start.elapsed() / ITERATIONS
+}
+
+unsafe fn call(entry_point: unsafe extern "C" fn(*const u8, usize, *mut usize) -> *mut u8, input: &[u8]) {
+    let mut len = 0;
+    let output = entry_point(black_box(input.as_ptr()), input.len(), &mut len);
+    free_result(black_box(output) as *mut _, len);
+}
+
+fn report(name: &str, json_input: &[u8], binary_input: &[u8]) {
+    let json = time(|| unsafe { call(evaluate, json_input) });
+    let binary = time(|| unsafe { call(evaluate_binary, binary_input) });
+
+    println!(
+        "{:<10} json {:>5} bytes {:>9.2?}   binary {:>5} bytes {:>9.2?}   {:.1}x",
+        name,
+        json_input.len(),
+        json,
+        binary_input.len(),
+        binary,
+        json.as_secs_f64() / binary.as_secs_f64()
+    );
+}
+
+fn main() {
+    let cases = [
+        ("to_upper", arguments("to_upper", &["stardog"])),
+        ("concat", arguments("concat", &["knowledge", "graph"])),
+    ];
+
+    for (name, values) in &cases {
+        report(name, &json(values), &binary::encode_arguments(values));
+    }
+}
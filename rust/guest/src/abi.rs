@@ -0,0 +1,140 @@
+//! Which calling convention a module speaks.
+//!
+//! Every module built with this crate exports `stardog_wasm_abi_version() -> u32`
+//! and `stardog_wasm_abi_features() -> u32`. A host reads both once per
+//! instance and looks the version up in [`COMPATIBILITY`]; modules that predate
+//! the exports are version 0. New conventions get a new version, so old
+//! modules keep working as long as hosts keep their row in the table. Rows
+//! with a feature bit are alternatives a module only has if it sets that bit.
+
+/// The version this crate builds modules for.
+pub const ABI_VERSION: u32 = 1;
+
+/// Bits of `stardog_wasm_abi_features()`. Optional parts of a convention that a
+/// host may use if present.
+pub mod features {
+    /// `internalEvaluate` is exported alongside `evaluate`.
+    pub const NUL_TERMINATED: u32 = 1 << 0;
+    /// `last_error`/`last_error_len` describe the last trap.
+    pub const LAST_ERROR: u32 = 1 << 1;
+    /// The first argument names the function to call.
+    pub const DISPATCH: u32 = 1 << 2;
+    /// `describe` returns a `ModuleDescription`.
+    pub const DESCRIBE: u32 = 1 << 3;
+    /// `evaluate_batch` evaluates many rows in one call.
+    pub const BATCH: u32 = 1 << 4;
+    /// `evaluate_binary` and `evaluate_batch_binary` take and return the
+    /// [`binary`](crate::binary) encoding instead of SPARQL JSON.
+    pub const BINARY: u32 = 1 << 5;
+    /// Calls allocate from an arena and `reset_heap` releases everything the
+    /// last one allocated, its result included.
+    pub const ARENA: u32 = 1 << 6;
+    /// `init(config, config_len) -> status` sets up the functions' state and
+    /// may be called again to replace it.
+    pub const INIT: u32 = 1 << 7;
+    /// `agg_init`, `agg_step`, `agg_merge`, `agg_finalize` and `agg_free`
+    /// run the module's aggregates.
+    pub const AGGREGATE: u32 = 1 << 8;
+    /// `cursor_open`, `cursor_next` and `cursor_close` page through the rows
+    /// of the module's table functions.
+    pub const CURSOR: u32 = 1 << 9;
+    /// `set_log_level(level)` turns logging on and `next_log_record(output_len)`
+    /// takes what the functions logged, oldest first, until it returns null.
+    /// A record is its level (1 error to 5 trace) as one byte followed by the
+    /// UTF-8 message, and is released with `free_result`.
+    ///
+    /// The module keeps the newest 1024 records. When it had to drop older
+    /// ones, the next record taken is a warning saying how many.
+    ///
+    /// These are exports rather than a `host_log` import because a module
+    /// that imports anything cannot be instantiated by hosts that supply no
+    /// imports, which wasmer-jni (and so `Call.java`) does not.
+    pub const HOST_LOG: u32 = 1 << 10;
+}
+
+/// The features every module built with this crate has.
+pub const BASE_FEATURES: u32 = features::LAST_ERROR
+    | features::BATCH
+    | features::BINARY
+    | if cfg!(feature = "nul-terminated") { features::NUL_TERMINATED } else { 0 }
+    | if cfg!(feature = "arena") { features::ARENA } else { 0 }
+    | if cfg!(feature = "host-log") { features::HOST_LOG } else { 0 };
+
+/// How arguments and results are laid out in memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Framing {
+    /// The input is written with a trailing NUL and the result pointer is
+    /// scanned for one.
+    NulTerminated,
+    /// The input length is passed alongside the pointer and the result length
+    /// is written to an out-parameter.
+    LengthDelimited,
+}
+
+/// How arguments and results are encoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// SPARQL 1.1 JSON select results, one `value[i]` row per argument.
+    SparqlJson,
+    /// The [`binary`](crate::binary) encoding.
+    Binary,
+}
+
+/// What a host has to do to call a module of a given ABI version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Convention {
+    pub abi_version: u32,
+    pub entry_point: &'static str,
+    pub framing: Framing,
+    pub encoding: Encoding,
+    /// The bit of [`features`] a module must set to have this convention, or
+    /// 0 for the one every module of the version has.
+    pub feature: u32,
+    /// Whether results have to be released with `free_result`. They leak in
+    /// modules that have no such export.
+    pub free_result: bool,
+}
+
+pub const COMPATIBILITY: &[Convention] = &[
+    Convention {
+        abi_version: 0,
+        entry_point: "internalEvaluate",
+        framing: Framing::NulTerminated,
+        encoding: Encoding::SparqlJson,
+        feature: 0,
+        free_result: false,
+    },
+    Convention {
+        abi_version: 1,
+        entry_point: "evaluate",
+        framing: Framing::LengthDelimited,
+        encoding: Encoding::SparqlJson,
+        feature: 0,
+        free_result: true,
+    },
+    Convention {
+        abi_version: 1,
+        entry_point: "evaluate_binary",
+        framing: Framing::LengthDelimited,
+        encoding: Encoding::Binary,
+        feature: features::BINARY,
+        free_result: true,
+    },
+];
+
+/// The convention for `abi_version`, or `None` if the module is newer than
+/// this table.
+pub fn convention(abi_version: u32) -> Option<&'static Convention> {
+    COMPATIBILITY.iter().find(|convention| convention.abi_version == abi_version && convention.feature == 0)
+}
+
+/// Every convention a module of `abi_version` with `features` can be called
+/// with, the one it always has first.
+pub fn conventions(abi_version: u32, features: u32) -> impl Iterator<Item = &'static Convention> {
+    COMPATIBILITY.iter().filter(move |convention| convention.abi_version == abi_version && supports(features, convention.feature))
+}
+
+/// Whether `features` has every bit of `required`.
+pub fn supports(features: u32, required: u32) -> bool {
+    features & required == required
+}
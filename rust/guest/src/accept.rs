@@ -0,0 +1,88 @@
+use crate::numeric::rank as numeric_rank;
+use crate::{Error, ErrorCode, Term, RDF_LANG_STRING, XSD};
+
+/// Which terms a parameter accepts, checked before the argument is converted.
+/// A term the rule does not accept is reported as an
+/// [`ErrorCode::TypeError`], as SPARQL would for a built-in.
+///
+/// `#[stardog_function]` takes the rule from an `#[accept(...)]` attribute on
+/// the parameter, and uses [`Accept::Any`] for parameters without one:
+///
+/// ```ignore
+/// #[stardog_function]
+/// fn levenshtein(#[accept(literal)] a: &str, #[accept(literal)] b: &str) -> usize {
+///     ...
+/// }
+///
+/// #[stardog_function]
+/// fn days_between(#[accept(datatype("xsd:date", "xsd:dateTime"))] from: &str, ...) -> ...
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Accept {
+    /// Any term, leaving it to the parameter's type to make sense of it.
+    Any,
+    /// `#[accept(iri)]`: IRIs only.
+    Iri,
+    /// `#[accept(triple)]`: RDF-star quoted triples only.
+    Triple,
+    /// `#[accept(plain_literal)]`: simple literals, `xsd:string`s and
+    /// language-tagged strings.
+    PlainLiteral,
+    /// `#[accept(literal)]`: any literal, coerced to its lexical form the way
+    /// `str()` does.
+    Literal,
+    /// `#[accept(numeric)]`: numeric literals that promote to the parameter's
+    /// datatype, so an `f64` takes integers, decimals, floats and doubles but
+    /// an `i64` only integers.
+    Numeric,
+    /// `#[accept(datatype("xsd:date", ...))]`: literals of one of these
+    /// datatypes.
+    Datatypes(&'static [&'static str]),
+}
+
+fn is_plain(term: &Term) -> bool {
+    match term.datatype() {
+        None => term.is_literal(),
+        Some(datatype) => datatype == RDF_LANG_STRING || datatype.strip_prefix(XSD) == Some("string"),
+    }
+}
+
+/// Whether `term` is a literal of `datatype`, counting simple literals as the
+/// `xsd:string`s they are since RDF 1.1.
+fn has_datatype(term: &Term, datatype: &str) -> bool {
+    match term.datatype() {
+        None => term.is_literal() && term.lang().is_none() && datatype.strip_prefix(XSD) == Some("string"),
+        Some(actual) => actual == datatype,
+    }
+}
+
+impl Accept {
+    /// Checks `term`, bound to `value[position]`, for a parameter whose
+    /// datatype is `target` (as in `FromArgument::TYPE`).
+    pub fn check(&self, position: usize, term: &Term, target: &str) -> Result<(), Error> {
+        let (accepted, expected) = match self {
+            Accept::Any => return Ok(()),
+            Accept::Iri => (term.is_iri(), "an IRI".to_owned()),
+            Accept::Triple => (term.is_triple(), "a quoted triple".to_owned()),
+            Accept::PlainLiteral => (is_plain(term), "a plain literal".to_owned()),
+            Accept::Literal => (term.is_literal(), "a literal".to_owned()),
+            Accept::Numeric => {
+                let rank = term.datatype().and_then(numeric_rank);
+                match numeric_rank(target) {
+                    Some(target_rank) => (rank.is_some_and(|rank| rank <= target_rank), format!("a numeric literal promotable to <{}>", target)),
+                    None => (rank.is_some(), "a numeric literal".to_owned()),
+                }
+            }
+            Accept::Datatypes(datatypes) => (
+                datatypes.iter().any(|datatype| has_datatype(term, datatype)),
+                format!("a literal of type {}", datatypes.iter().map(|datatype| format!("<{}>", datatype)).collect::<Vec<_>>().join(" or ")),
+            ),
+        };
+
+        if accepted {
+            Ok(())
+        } else {
+            Err(Error::new(ErrorCode::TypeError, format!("value[{}] ({}) is not {}", position, term, expected)))
+        }
+    }
+}
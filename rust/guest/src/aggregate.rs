@@ -0,0 +1,252 @@
+use std::any::Any;
+use std::os::raw::c_void;
+
+use crate::{panic, Args, Error, ErrorCode, IntoResults, SelectResults};
+
+/// A custom SPARQL aggregate, exported with the `aggregates` section of
+/// [`export_functions!`].
+///
+/// The host starts a state per group with `agg_init`, feeds it every row of
+/// the group with `agg_step`, may combine states built in parallel with
+/// `agg_merge` and gets the result with `agg_finalize`:
+///
+/// ```ignore
+/// #[derive(Default)]
+/// struct Median {
+///     values: Vec<f64>,
+/// }
+///
+/// impl Aggregate for Median {
+///     const NAME: &'static str = "median";
+///     type Output = Result<f64, Error>;
+///
+///     fn step(&mut self, args: &Args) -> Result<(), Error> {
+///         self.values.push(args.require(0)?);
+///         Ok(())
+///     }
+///
+///     fn merge(&mut self, other: Median) {
+///         self.values.extend(other.values);
+///     }
+///
+///     fn finalize(self) -> Result<f64, Error> {
+///         ...
+///     }
+/// }
+/// ```
+pub trait Aggregate: Default + 'static {
+    /// The name `agg_init` selects the aggregate by.
+    const NAME: &'static str;
+    /// What `describe` says the aggregate does.
+    const DESCRIPTION: &'static str = "";
+
+    type Output: IntoResults;
+
+    /// Adds one row. `args` are the arguments of the aggregate for that row,
+    /// after the module and the aggregate name.
+    fn step(&mut self, args: &Args) -> Result<(), Error>;
+
+    /// Adds everything `other` has seen.
+    fn merge(&mut self, other: Self);
+
+    fn finalize(self) -> Self::Output;
+}
+
+/// An aggregate state behind a handle, whatever the aggregate.
+trait State {
+    fn step(&mut self, args: &Args) -> Result<(), Error>;
+
+    fn merge(&mut self, other: Box<dyn State>) -> Result<(), Error>;
+
+    fn finalize(self: Box<Self>) -> Result<SelectResults, Error>;
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<A: Aggregate> State for A {
+    fn step(&mut self, args: &Args) -> Result<(), Error> {
+        Aggregate::step(self, args)
+    }
+
+    fn merge(&mut self, other: Box<dyn State>) -> Result<(), Error> {
+        let other = other
+            .into_any()
+            .downcast::<A>()
+            .map_err(|_| Error::failed(format!("cannot merge a state of another aggregate into `{}`", A::NAME)))?;
+        Aggregate::merge(self, *other);
+
+        Ok(())
+    }
+
+    fn finalize(self: Box<Self>) -> Result<SelectResults, Error> {
+        Aggregate::finalize(*self).into_results()
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// A registered aggregate: its name and how to start a state for it.
+pub type AggregateEntry = (&'static str, fn() -> Handle);
+
+/// What `agg_init` and `cursor_open` return and the other `agg_` and `cursor_`
+/// exports take. It points to an aggregate state or a cursor in guest memory
+/// that belongs to the host until `agg_finalize`, `agg_free` or
+/// `cursor_close`.
+pub type Handle = *mut c_void;
+
+#[doc(hidden)]
+pub fn new_state<A: Aggregate>() -> Handle {
+    let state: Box<dyn State> = Box::new(A::default());
+
+    Box::into_raw(Box::new(state)) as Handle
+}
+
+unsafe fn state<'a>(handle: Handle) -> &'a mut Box<dyn State> {
+    &mut *(handle as *mut Box<dyn State>)
+}
+
+unsafe fn take_state(handle: Handle) -> Box<dyn State> {
+    *Box::from_raw(handle as *mut Box<dyn State>)
+}
+
+/// Reports `result` the way the `agg_` exports that return a status do: 0 if
+/// it is `Ok`, otherwise 1 with the error in `last_error`.
+fn status(result: Result<(), Error>) -> u32 {
+    match result {
+        Ok(()) => 0,
+        Err(error) => {
+            panic::set_last_error(error.to_string());
+            1
+        }
+    }
+}
+
+/// Starts a state for the aggregate named by the `name_len` bytes at `name`,
+/// or returns null with the reason in `last_error`.
+///
+/// # Safety
+///
+/// `name` must point to `name_len` readable bytes. Called by the code
+/// [`export_functions!`] generates; not meant to be used directly.
+#[doc(hidden)]
+pub unsafe fn agg_init(name: *const u8, name_len: usize, aggregates: &[AggregateEntry]) -> Handle {
+    panic::install_hook();
+    panic::clear_last_error();
+
+    let name = String::from_utf8_lossy(std::slice::from_raw_parts(name, name_len));
+    match aggregates.iter().find(|(candidate, _)| *candidate == name) {
+        Some((_, init)) => init(),
+        None => {
+            panic::set_last_error(Error::new(ErrorCode::UnknownFunction, format!("no aggregate named `{}`", name)).to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Adds the row at `input`, encoded as for `evaluate`, to `handle`.
+///
+/// # Safety
+///
+/// `handle` must be a live state and `input` must point to `input_len`
+/// readable bytes.
+#[doc(hidden)]
+pub unsafe fn agg_step(handle: Handle, input: *const u8, input_len: usize) -> u32 {
+    panic::install_hook();
+    panic::clear_last_error();
+
+    let input = std::str::from_utf8(std::slice::from_raw_parts(input, input_len));
+    let result = crate::decode_input(input, Args::parse).and_then(|args| state(handle).step(&args.skip(1)));
+
+    status(result)
+}
+
+/// Merges the state `other` into `handle`, consuming `other` either way.
+///
+/// # Safety
+///
+/// Both must be live states, and different ones.
+#[doc(hidden)]
+pub unsafe fn agg_merge(handle: Handle, other: Handle) -> u32 {
+    panic::install_hook();
+    panic::clear_last_error();
+
+    status(state(handle).merge(take_state(other)))
+}
+
+/// Consumes `handle` and returns its result the way `evaluate` does.
+///
+/// # Safety
+///
+/// `handle` must be a live state and `output_len` must point to a writable
+/// `usize`.
+#[doc(hidden)]
+pub unsafe fn agg_finalize(handle: Handle, output_len: *mut usize) -> *mut u8 {
+    panic::install_hook();
+    panic::clear_last_error();
+
+    let output = crate::result_document(take_state(handle).finalize());
+
+    crate::into_sized_result_buffer(output, output_len)
+}
+
+/// Drops a state the host no longer needs a result for.
+///
+/// # Safety
+///
+/// `handle` must be a live state.
+#[doc(hidden)]
+pub unsafe fn agg_free(handle: Handle) {
+    drop(take_state(handle));
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_aggregates {
+    ($($aggregate:path),+) => {
+        const __STARDOG_WASM_AGGREGATES: &[$crate::AggregateEntry] =
+            &[$((<$aggregate as $crate::Aggregate>::NAME, $crate::new_state::<$aggregate>)),+];
+
+        /// # Safety
+        ///
+        /// Called by the host with a buffer obtained from `allocate`.
+        #[export_name = "agg_init"]
+        pub unsafe extern "C" fn __stardog_wasm_agg_init(name: *const u8, name_len: usize) -> $crate::Handle {
+            $crate::agg_init(name, name_len, __STARDOG_WASM_AGGREGATES)
+        }
+
+        /// # Safety
+        ///
+        /// Called by the host with a state from `agg_init` and a buffer
+        /// obtained from `allocate`.
+        #[export_name = "agg_step"]
+        pub unsafe extern "C" fn __stardog_wasm_agg_step(state: $crate::Handle, input: *const u8, input_len: usize) -> u32 {
+            $crate::agg_step(state, input, input_len)
+        }
+
+        /// # Safety
+        ///
+        /// Called by the host with two states from `agg_init`.
+        #[export_name = "agg_merge"]
+        pub unsafe extern "C" fn __stardog_wasm_agg_merge(state: $crate::Handle, other: $crate::Handle) -> u32 {
+            $crate::agg_merge(state, other)
+        }
+
+        /// # Safety
+        ///
+        /// Called by the host with a state from `agg_init`.
+        #[export_name = "agg_finalize"]
+        pub unsafe extern "C" fn __stardog_wasm_agg_finalize(state: $crate::Handle, output_len: *mut usize) -> *mut u8 {
+            $crate::agg_finalize(state, output_len)
+        }
+
+        /// # Safety
+        ///
+        /// Called by the host with a state from `agg_init`.
+        #[export_name = "agg_free"]
+        pub unsafe extern "C" fn __stardog_wasm_agg_free(state: $crate::Handle) {
+            $crate::agg_free(state)
+        }
+    };
+}
@@ -0,0 +1,256 @@
+//! The `arena` feature: a global allocator that serves everything allocated
+//! during a call from a bump arena.
+//!
+//! Freeing arena memory does nothing; the whole arena is reset instead, either
+//! by the host calling `reset_heap` once it has read the result or, at the
+//! latest, when the next call starts. Memory no longer grows with the number
+//! of calls an instance has served, however its allocations were interleaved.
+//!
+//! Allocations made outside calls (the host's `allocate`, aggregate states,
+//! anything a function keeps in a `static`, the panic hook) still come from
+//! the system allocator. `dealloc` and `realloc` tell the two apart by
+//! address, so a buffer from before the call that grows during it stays with
+//! the system allocator. A function must still not stash anything it
+//! allocates while running for a later call to use.
+//!
+//! wasm32 instances are single-threaded, so the arena is per thread and other
+//! threads (in native tests, say) are unaffected by it.
+
+#[cfg(feature = "arena")]
+pub(crate) use self::enabled::*;
+
+#[cfg(not(feature = "arena"))]
+pub(crate) use self::disabled::*;
+
+#[cfg(feature = "arena")]
+mod enabled {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+    use std::mem;
+    use std::ptr;
+
+    /// The smallest chunk the arena asks the system allocator for.
+    const FIRST_CHUNK: usize = 64 * 1024;
+    const ALIGN: usize = 16;
+
+    /// The start of every chunk; the arena's memory follows it.
+    struct Chunk {
+        previous: *mut Chunk,
+        size: usize,
+    }
+
+    const HEADER: usize = (mem::size_of::<Chunk>() + ALIGN - 1) & !(ALIGN - 1);
+
+    struct Arena {
+        /// How many calls are running; the arena is only used while this is
+        /// not zero.
+        depth: Cell<usize>,
+        /// The newest chunk, linked to the older ones.
+        chunk: Cell<*mut Chunk>,
+        /// Bytes of the newest chunk in use, header included.
+        used: Cell<usize>,
+        /// How big to make the first chunk after a reset.
+        reserve: Cell<usize>,
+    }
+
+    thread_local! {
+        static ARENA: Arena = const {
+            Arena {
+                depth: Cell::new(0),
+                chunk: Cell::new(ptr::null_mut()),
+                used: Cell::new(0),
+                reserve: Cell::new(FIRST_CHUNK),
+            }
+        };
+    }
+
+    impl Arena {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let chunk = self.chunk.get();
+            if !chunk.is_null() {
+                let start = (chunk as usize + self.used.get() + layout.align() - 1) & !(layout.align() - 1);
+                let end = start + layout.size();
+                if end <= chunk as usize + (*chunk).size {
+                    self.used.set(end - chunk as usize);
+                    return start as *mut u8;
+                }
+            }
+
+            let previous = if chunk.is_null() { self.reserve.get() } else { (*chunk).size * 2 };
+            let size = previous.max(HEADER + layout.size() + layout.align());
+            let new = System.alloc(Layout::from_size_align_unchecked(size, ALIGN)) as *mut Chunk;
+            if new.is_null() {
+                return ptr::null_mut();
+            }
+            new.write(Chunk { previous: chunk, size });
+            self.chunk.set(new);
+            self.used.set(HEADER);
+
+            self.alloc(layout)
+        }
+
+        /// Resizes the last allocation where it is, which is what a growing
+        /// `Vec` or `String` usually asks for.
+        unsafe fn resize_last(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> bool {
+            let chunk = self.chunk.get();
+            if chunk.is_null() || pointer as usize + layout.size() != chunk as usize + self.used.get() {
+                return false;
+            }
+
+            let end = pointer as usize + new_size;
+            if end > chunk as usize + (*chunk).size {
+                return false;
+            }
+            self.used.set(end - chunk as usize);
+
+            true
+        }
+
+        fn contains(&self, pointer: *mut u8) -> bool {
+            let mut chunk = self.chunk.get();
+            while !chunk.is_null() {
+                unsafe {
+                    if (chunk as usize..chunk as usize + (*chunk).size).contains(&(pointer as usize)) {
+                        return true;
+                    }
+                    chunk = (*chunk).previous;
+                }
+            }
+
+            false
+        }
+
+        /// Empties the arena. If the last call needed more than one chunk they
+        /// are given back and the next call gets one chunk as big as all of
+        /// them, so a steady workload settles on a single chunk.
+        fn reset(&self) {
+            let newest = self.chunk.get();
+            unsafe {
+                if newest.is_null() || (*newest).previous.is_null() {
+                    self.used.set(HEADER);
+                    return;
+                }
+
+                let mut total = 0;
+                let mut chunk = newest;
+                while !chunk.is_null() {
+                    let previous = (*chunk).previous;
+                    total += (*chunk).size;
+                    System.dealloc(chunk as *mut u8, Layout::from_size_align_unchecked((*chunk).size, ALIGN));
+                    chunk = previous;
+                }
+                self.chunk.set(ptr::null_mut());
+                self.reserve.set(total);
+            }
+        }
+    }
+
+    struct Allocator;
+
+    unsafe impl GlobalAlloc for Allocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ARENA.with(|arena| {
+                if arena.depth.get() > 0 {
+                    arena.alloc(layout)
+                } else {
+                    System.alloc(layout)
+                }
+            })
+        }
+
+        unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+            ARENA.with(|arena| {
+                if !arena.contains(pointer) {
+                    System.dealloc(pointer, layout)
+                }
+            })
+        }
+
+        unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let (active, contained) = ARENA.with(|arena| (arena.depth.get() > 0, arena.contains(pointer)));
+            // memory from before the call has to outlive it, so it stays where it is
+            if !contained {
+                return System.realloc(pointer, layout, new_size);
+            }
+            if active && ARENA.with(|arena| arena.resize_last(pointer, layout, new_size)) {
+                return pointer;
+            }
+
+            let new = self.alloc(Layout::from_size_align_unchecked(new_size, layout.align()));
+            if !new.is_null() {
+                ptr::copy_nonoverlapping(pointer, new, layout.size().min(new_size));
+                self.dealloc(pointer, layout);
+            }
+
+            new
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: Allocator = Allocator;
+
+    /// One call into the module. Starting one resets the arena, so the
+    /// previous call's result must have been read by then.
+    pub(crate) struct Scope(());
+
+    impl Scope {
+        pub(crate) fn enter() -> Scope {
+            ARENA.with(|arena| {
+                if arena.depth.get() == 0 {
+                    arena.reset();
+                }
+                arena.depth.set(arena.depth.get() + 1);
+            });
+
+            Scope(())
+        }
+    }
+
+    impl Drop for Scope {
+        fn drop(&mut self) {
+            ARENA.with(|arena| arena.depth.set(arena.depth.get() - 1));
+        }
+    }
+
+    /// Runs `f` with the system allocator, for what has to outlive the call.
+    pub(crate) fn outside<T, F: FnOnce() -> T>(f: F) -> T {
+        struct Restore(usize);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                ARENA.with(|arena| arena.depth.set(self.0));
+            }
+        }
+
+        let _restore = Restore(ARENA.with(|arena| arena.depth.replace(0)));
+
+        f()
+    }
+
+    /// Releases everything allocated by the last call, its result included,
+    /// which must not be passed to `free_result` afterwards. Optional: the
+    /// next call does the same first thing anyway.
+    #[no_mangle]
+    pub extern "C" fn reset_heap() {
+        ARENA.with(|arena| {
+            if arena.depth.get() == 0 {
+                arena.reset();
+            }
+        })
+    }
+}
+
+#[cfg(not(feature = "arena"))]
+mod disabled {
+    pub(crate) struct Scope(());
+
+    impl Scope {
+        pub(crate) fn enter() -> Scope {
+            Scope(())
+        }
+    }
+
+    pub(crate) fn outside<T, F: FnOnce() -> T>(f: F) -> T {
+        f()
+    }
+}
@@ -0,0 +1,278 @@
+use std::borrow::Cow;
+use std::fmt;
+use serde::de::{DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use crate::{Accept, Error, FromArgument, Term};
+
+/// Arguments passed to `wasm:call`, not counting the module IRI in `value[0]`.
+///
+/// Arguments borrow their strings from the input buffer unless they had to
+/// be unescaped, so taking `&str` parameters costs no allocation per argument.
+#[derive(Clone, Debug)]
+pub struct Args<'a> {
+    values: Cow<'a, [Option<Term<'a>>]>,
+    /// The `value[i]` that argument 0 was bound to.
+    first: usize,
+}
+
+impl<'a> Args<'a> {
+    /// Decodes the SPARQL JSON document the host sends.
+    ///
+    /// The host writes one row per argument, each binding a single `value[i]`
+    /// variable, so arguments are placed by the index in the variable name
+    /// rather than by row position.
+    pub fn parse(input: &'a str) -> serde_json::Result<Args<'a>> {
+        let mut values = Vec::new();
+        read_document(input, &mut |_, index, term| place(&mut values, index, term))?;
+
+        Ok(Args::from_values(values))
+    }
+
+    /// Decodes the document `evaluate_batch` takes, one row per call with
+    /// every `value[i]` of the call bound in that row.
+    pub fn parse_batch(input: &'a str) -> serde_json::Result<Vec<Args<'a>>> {
+        let mut batch: Vec<Vec<Option<Term<'a>>>> = Vec::new();
+        read_document(input, &mut |row, index, term| {
+            if batch.len() <= row {
+                batch.resize_with(row + 1, Vec::new);
+            }
+            place(&mut batch[row], index, term);
+        })?;
+
+        Ok(batch.into_iter().map(Args::from_values).collect())
+    }
+
+    /// The arguments from the terms bound to `value[0]`, `value[1]`, ...
+    pub(crate) fn from_values(mut values: Vec<Option<Term<'a>>>) -> Args<'a> {
+        if !values.is_empty() {
+            values.remove(0);
+        }
+
+        Args { values: Cow::Owned(values), first: 1 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The arguments after the first `n`.
+    pub fn skip(&self, n: usize) -> Args<'_> {
+        Args { values: Cow::Borrowed(self.values.get(n..).unwrap_or_default()), first: self.first + n }
+    }
+
+    /// The `value[i]` that argument `index` was bound to, which is how the
+    /// host numbers it and so how errors refer to it.
+    pub fn position(&self, index: usize) -> usize {
+        self.first + index
+    }
+
+    /// Argument `index`, counting from zero.
+    pub fn term(&self, index: usize) -> Option<&Term<'a>> {
+        self.values.get(index).and_then(Option::as_ref)
+    }
+
+    /// The lexical value of argument `index`, counting from zero.
+    pub fn value(&self, index: usize) -> Option<&str> {
+        self.term(index).and_then(Term::value)
+    }
+
+    /// Argument `index` converted to `T`, or `None` if it is missing or
+    /// cannot be converted.
+    pub fn argument<'s, T: FromArgument<'s>>(&'s self, index: usize) -> Option<T> {
+        self.term(index).and_then(T::from_argument)
+    }
+
+    /// Like [`Args::argument`], but says why the argument is unusable.
+    pub fn require<'s, T: FromArgument<'s>>(&'s self, index: usize) -> Result<T, Error> {
+        self.require_accepted(index, &Accept::Any)
+    }
+
+    /// Like [`Args::require`], but first checks the argument against `accept`.
+    pub fn require_accepted<'s, T: FromArgument<'s>>(&'s self, index: usize, accept: &Accept) -> Result<T, Error> {
+        T::from_arguments(self, index, accept)
+    }
+}
+
+fn place<'a>(values: &mut Vec<Option<Term<'a>>>, index: usize, term: Term<'a>) {
+    if values.len() <= index {
+        values.resize(index + 1, None);
+    }
+    values[index] = Some(term);
+}
+
+fn argument_index(var: &str) -> Option<usize> {
+    var.strip_prefix("value[")?.strip_suffix(']')?.parse().ok()
+}
+
+/// Calls `bind(row, index, term)` for every `value[index]` in `input`, without
+/// building the document itself.
+fn read_document<'a>(input: &'a str, bind: &mut dyn FnMut(usize, usize, Term<'a>)) -> serde_json::Result<()> {
+    let mut deserializer = serde_json::Deserializer::from_str(input);
+    Reader { section: Section::Document, bind }.deserialize(&mut deserializer)?;
+
+    deserializer.end()
+}
+
+/// The parts of `{"results": {"bindings": [{"value[i]": term}, ...]}}` that
+/// lead to the terms. Everything else is skipped.
+#[derive(Clone, Copy)]
+enum Section {
+    Document,
+    Results,
+    Bindings,
+    Row(usize),
+}
+
+struct Reader<'b, 'a> {
+    section: Section,
+    bind: &'b mut dyn FnMut(usize, usize, Term<'a>),
+}
+
+impl<'a> DeserializeSeed<'a> for Reader<'_, 'a> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'a>>(self, deserializer: D) -> Result<(), D::Error> {
+        match self.section {
+            Section::Bindings => deserializer.deserialize_seq(self),
+            _ => deserializer.deserialize_map(self),
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Reader<'_, 'a> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SPARQL JSON select results")
+    }
+
+    fn visit_map<M: MapAccess<'a>>(self, mut map: M) -> Result<(), M::Error> {
+        while let Some(Key(key)) = map.next_key()? {
+            let section = match (self.section, &*key) {
+                (Section::Document, "results") => Section::Results,
+                (Section::Results, "bindings") => Section::Bindings,
+                (Section::Row(row), var) if argument_index(var).is_some() => {
+                    let term = map.next_value_seed(BorrowedTerm)?;
+                    (self.bind)(row, argument_index(var).expect("checked above"), term);
+                    continue;
+                }
+                _ => {
+                    map.next_value::<Skip>()?;
+                    continue;
+                }
+            };
+
+            map.next_value_seed(Reader { section, bind: &mut *self.bind })?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_seq<S: SeqAccess<'a>>(self, mut seq: S) -> Result<(), S::Error> {
+        let mut row = 0;
+        while seq.next_element_seed(Reader { section: Section::Row(row), bind: &mut *self.bind })?.is_some() {
+            row += 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// A map key, borrowed unless it had escapes.
+struct Key<'a>(Cow<'a, str>);
+
+impl<'a> Deserialize<'a> for Key<'a> {
+    fn deserialize<D: Deserializer<'a>>(deserializer: D) -> Result<Key<'a>, D::Error> {
+        struct KeyVisitor;
+
+        impl<'a> Visitor<'a> for KeyVisitor {
+            type Value = Key<'a>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_borrowed_str<E>(self, key: &'a str) -> Result<Key<'a>, E> {
+                Ok(Key(Cow::Borrowed(key)))
+            }
+
+            fn visit_str<E>(self, key: &str) -> Result<Key<'a>, E> {
+                Ok(Key(Cow::Owned(key.to_owned())))
+            }
+        }
+
+        deserializer.deserialize_str(KeyVisitor)
+    }
+}
+
+struct BorrowedTerm;
+
+impl<'a> DeserializeSeed<'a> for BorrowedTerm {
+    type Value = Term<'a>;
+
+    fn deserialize<D: Deserializer<'a>>(self, deserializer: D) -> Result<Term<'a>, D::Error> {
+        Term::deserialize_borrowed(deserializer)
+    }
+}
+
+/// Any value, read and dropped. Unlike `IgnoredAny`, which serde_json skips
+/// with a heap-allocated stack of brackets, this recurses, so the `vars` in the
+/// head cost nothing.
+struct Skip;
+
+impl<'a> Deserialize<'a> for Skip {
+    fn deserialize<D: Deserializer<'a>>(deserializer: D) -> Result<Skip, D::Error> {
+        deserializer.deserialize_any(Skip)
+    }
+}
+
+impl<'a> Visitor<'a> for Skip {
+    type Value = Skip;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Skip, E> {
+        Ok(Skip)
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Skip, E> {
+        Ok(Skip)
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Skip, E> {
+        Ok(Skip)
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Skip, E> {
+        Ok(Skip)
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<Skip, E> {
+        Ok(Skip)
+    }
+
+    fn visit_unit<E>(self) -> Result<Skip, E> {
+        Ok(Skip)
+    }
+
+    fn visit_seq<S: SeqAccess<'a>>(self, mut seq: S) -> Result<Skip, S::Error> {
+        while seq.next_element::<Skip>()?.is_some() {}
+
+        Ok(Skip)
+    }
+
+    fn visit_map<M: MapAccess<'a>>(self, mut map: M) -> Result<Skip, M::Error> {
+        while map.next_key::<Skip>()?.is_some() {
+            map.next_value::<Skip>()?;
+        }
+
+        Ok(Skip)
+    }
+}
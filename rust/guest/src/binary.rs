@@ -0,0 +1,310 @@
+//! A compact alternative to SPARQL JSON, for `evaluate_binary` and
+//! `evaluate_batch_binary`.
+//!
+//! Everything is little-endian and there is no padding. A string is a `u32`
+//! byte length followed by that many bytes of UTF-8. A term is a tag byte
+//! followed by its strings, or for a quoted triple its terms:
+//!
+//! | tag | term                | followed by                |
+//! |-----|---------------------|----------------------------|
+//! | 0   | unbound             |                            |
+//! | 1   | IRI                 | IRI                        |
+//! | 2   | blank node          | label                      |
+//! | 3   | simple literal      | lexical form               |
+//! | 4   | datatyped literal   | lexical form, datatype     |
+//! | 5   | language literal    | lexical form, tag          |
+//! | 6   | quoted triple       | subject, predicate, object |
+//!
+//! The terms of a quoted triple are never unbound, and quoted triples nest at
+//! most 64 deep.
+//!
+//! The arguments of a call are a `u32` count followed by that many terms,
+//! `value[0]` first, exactly the terms the SPARQL JSON input would bind.
+//!
+//! A result is a tag byte. `0` is followed by a `u32` count and that many
+//! variable names, then a `u32` row count and, for every row, one term per
+//! variable. `1` is an error and is followed by its code (as the kebab-case
+//! string the JSON error uses) and its message.
+//!
+//! A batch is a `u32` count followed by that many arguments (for the input)
+//! or results (for the output).
+
+use std::borrow::Cow;
+use std::convert::TryInto;
+
+use crate::{Args, Binding, Error, ErrorCode, SelectResults, Term};
+
+const UNBOUND: u8 = 0;
+const IRI: u8 = 1;
+const BLANK_NODE: u8 = 2;
+const SIMPLE_LITERAL: u8 = 3;
+const TYPED_LITERAL: u8 = 4;
+const LANG_LITERAL: u8 = 5;
+const TRIPLE: u8 = 6;
+
+/// How deep quoted triples may nest, so that reading one cannot run out of
+/// stack.
+const MAX_NESTING: usize = 64;
+
+const RESULTS: u8 = 0;
+const ERROR: u8 = 1;
+
+/// Encodes the arguments of one call, `value[0]` first.
+pub fn encode_arguments(values: &[Option<Term>]) -> Vec<u8> {
+    let mut writer = Writer::default();
+    writer.arguments(values);
+
+    writer.bytes
+}
+
+/// Encodes the arguments of every call of a batch.
+pub fn encode_batch_arguments(batch: &[Vec<Option<Term>>]) -> Vec<u8> {
+    let mut writer = Writer::default();
+    writer.len(batch.len());
+    for values in batch {
+        writer.arguments(values);
+    }
+
+    writer.bytes
+}
+
+pub fn decode_arguments(input: &[u8]) -> Result<Args<'_>, Error> {
+    let mut reader = Reader::new(input);
+    let args = reader.arguments()?;
+    reader.finish()?;
+
+    Ok(args)
+}
+
+pub fn decode_batch_arguments(input: &[u8]) -> Result<Vec<Args<'_>>, Error> {
+    let mut reader = Reader::new(input);
+    let batch = (0..reader.len()?).map(|_| reader.arguments()).collect::<Result<_, _>>()?;
+    reader.finish()?;
+
+    Ok(batch)
+}
+
+/// Encodes a result, which is an error if `results.error` is set.
+pub fn encode_results(results: &SelectResults) -> Vec<u8> {
+    let mut writer = Writer::default();
+    writer.results(results);
+
+    writer.bytes
+}
+
+pub fn encode_batch_results(batch: &[SelectResults]) -> Vec<u8> {
+    let mut writer = Writer::default();
+    writer.len(batch.len());
+    for results in batch {
+        writer.results(results);
+    }
+
+    writer.bytes
+}
+
+pub fn decode_results(input: &[u8]) -> Result<SelectResults, Error> {
+    let mut reader = Reader::new(input);
+    let results = reader.results()?;
+    reader.finish()?;
+
+    Ok(results)
+}
+
+pub fn decode_batch_results(input: &[u8]) -> Result<Vec<SelectResults>, Error> {
+    let mut reader = Reader::new(input);
+    let batch = (0..reader.len()?).map(|_| reader.results()).collect::<Result<_, _>>()?;
+    reader.finish()?;
+
+    Ok(batch)
+}
+
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn len(&mut self, len: usize) {
+        self.bytes.extend_from_slice(&(len as u32).to_le_bytes());
+    }
+
+    fn string(&mut self, string: &str) {
+        self.len(string.len());
+        self.bytes.extend_from_slice(string.as_bytes());
+    }
+
+    fn term(&mut self, term: Option<&Term>) {
+        match term {
+            None => self.bytes.push(UNBOUND),
+            Some(Term::Iri(iri)) => {
+                self.bytes.push(IRI);
+                self.string(iri);
+            }
+            Some(Term::BlankNode(id)) => {
+                self.bytes.push(BLANK_NODE);
+                self.string(id);
+            }
+            Some(Term::Literal { lexical, datatype: None, lang: None }) => {
+                self.bytes.push(SIMPLE_LITERAL);
+                self.string(lexical);
+            }
+            Some(Term::Literal { lexical, lang: Some(lang), .. }) => {
+                self.bytes.push(LANG_LITERAL);
+                self.string(lexical);
+                self.string(lang);
+            }
+            Some(Term::Literal { lexical, datatype: Some(datatype), .. }) => {
+                self.bytes.push(TYPED_LITERAL);
+                self.string(lexical);
+                self.string(datatype);
+            }
+            Some(Term::Triple(triple)) => {
+                self.bytes.push(TRIPLE);
+                self.term(Some(&triple.subject));
+                self.term(Some(&triple.predicate));
+                self.term(Some(&triple.object));
+            }
+        }
+    }
+
+    fn arguments(&mut self, values: &[Option<Term>]) {
+        self.len(values.len());
+        for value in values {
+            self.term(value.as_ref());
+        }
+    }
+
+    fn results(&mut self, results: &SelectResults) {
+        if let Some(error) = &results.error {
+            self.bytes.push(ERROR);
+            self.string(error.code.as_str());
+            self.string(&error.message);
+            return;
+        }
+
+        self.bytes.push(RESULTS);
+        self.len(results.vars().len());
+        for var in results.vars() {
+            self.string(var);
+        }
+        self.len(results.bindings().len());
+        for binding in results.bindings() {
+            for var in results.vars() {
+                self.term(binding.get(var));
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    /// How many quoted triples the term being read is inside of.
+    nesting: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes, nesting: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < len {
+            return Err(invalid("input ends early"));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Ok(taken)
+    }
+
+    fn tag(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn len(&mut self) -> Result<usize, Error> {
+        let bytes = self.take(4)?.try_into().expect("took four bytes");
+
+        Ok(u32::from_le_bytes(bytes) as usize)
+    }
+
+    fn string(&mut self) -> Result<Cow<'a, str>, Error> {
+        let len = self.len()?;
+        let bytes = self.take(len)?;
+
+        std::str::from_utf8(bytes)
+            .map(Cow::Borrowed)
+            .map_err(|e| invalid(format!("string is not UTF-8: {}", e)))
+    }
+
+    fn term(&mut self) -> Result<Option<Term<'a>>, Error> {
+        Ok(Some(match self.tag()? {
+            UNBOUND => return Ok(None),
+            IRI => Term::Iri(self.string()?),
+            BLANK_NODE => Term::BlankNode(self.string()?),
+            SIMPLE_LITERAL => Term::literal(self.string()?),
+            TYPED_LITERAL => Term::typed_literal(self.string()?, self.string()?),
+            LANG_LITERAL => Term::lang_literal(self.string()?, self.string()?),
+            TRIPLE => {
+                if self.nesting == MAX_NESTING {
+                    return Err(invalid("quoted triples nest too deep"));
+                }
+                self.nesting += 1;
+                let triple = Term::triple(self.quoted_term()?, self.quoted_term()?, self.quoted_term()?);
+                self.nesting -= 1;
+
+                triple
+            }
+            tag => return Err(invalid(format!("unknown term tag {}", tag))),
+        }))
+    }
+
+    /// A term of a quoted triple, which cannot be unbound.
+    fn quoted_term(&mut self) -> Result<Term<'a>, Error> {
+        self.term()?.ok_or_else(|| invalid("quoted triple has an unbound term"))
+    }
+
+    fn arguments(&mut self) -> Result<Args<'a>, Error> {
+        let values = (0..self.len()?).map(|_| self.term()).collect::<Result<_, _>>()?;
+
+        Ok(Args::from_values(values))
+    }
+
+    fn results(&mut self) -> Result<SelectResults, Error> {
+        match self.tag()? {
+            RESULTS => {
+                let vars: Vec<String> = (0..self.len()?).map(|_| self.string().map(Cow::into_owned)).collect::<Result<_, _>>()?;
+                let mut results = SelectResults::new(vars.clone());
+                for _ in 0..self.len()? {
+                    let mut binding = Binding::new();
+                    for var in &vars {
+                        if let Some(term) = self.term()? {
+                            binding = binding.with(var.clone(), term.into_owned());
+                        }
+                    }
+                    results.push(binding);
+                }
+
+                Ok(results)
+            }
+            ERROR => {
+                let code = self.string()?;
+                let code = code.parse().map_err(|_| invalid(format!("unknown error code `{}`", code)))?;
+
+                Ok(SelectResults::error(Error::new(code, self.string()?)))
+            }
+            tag => Err(invalid(format!("unknown result tag {}", tag))),
+        }
+    }
+
+    fn finish(&self) -> Result<(), Error> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid(format!("{} bytes left over", self.bytes.len())))
+        }
+    }
+}
+
+fn invalid<S: Into<String>>(message: S) -> Error {
+    Error::new(ErrorCode::InvalidInput, message)
+}
@@ -0,0 +1,406 @@
+use std::convert::TryFrom;
+
+use crate::datetime::is_temporal;
+use crate::lang::RDF_LANG_STRING;
+use crate::numeric::{self, canonical_double, canonical_float};
+use crate::{Accept, Args, Date, DateTime, Decimal, Duration, Error, ErrorCode, GYear, Integer, LangString, LanguageTag, Numeric, Term, Time, Triple};
+
+pub const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// Type reported by `describe` for parameters and results that may be any term.
+pub const ANY_TERM: &str = "term";
+
+/// Type reported by `describe` for parameters and results that may be a
+/// literal of any numeric datatype.
+pub const NUMERIC: &str = "numeric";
+
+/// Type reported by `describe` for parameters and results that are RDF-star
+/// quoted triples.
+pub const TRIPLE: &str = "triple";
+
+macro_rules! xsd {
+    ($name:literal) => {
+        concat!("http://www.w3.org/2001/XMLSchema#", $name)
+    };
+}
+
+/// Conversion from a `wasm:call` argument to a function parameter.
+///
+/// A type with no implementation has no RDF mapping, and using it as a
+/// parameter does not compile:
+///
+/// ```compile_fail
+/// use stardog_wasm_guest::stardog_function;
+///
+/// struct Point {
+///     x: f64,
+///     y: f64,
+/// }
+///
+/// #[stardog_function]
+/// fn norm(point: Point) -> f64 {
+///     point.x.hypot(point.y)
+/// }
+/// ```
+///
+/// Structs with one field can derive it to take whatever that field takes.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no RDF mapping, so it cannot be a function parameter",
+    label = "no conversion from an RDF term",
+    note = "parameters can be terms, strings, booleans, numbers, dates, durations or `LangString`s, or an `Option` or `Vec` of them",
+    note = "a struct with one such field can `#[derive(FromArgument)]`"
+)]
+pub trait FromArgument<'a>: Sized {
+    /// The datatype IRI `describe` reports for the parameter, or [`ANY_TERM`].
+    const TYPE: &'static str;
+    /// Whether the argument may be left out.
+    const OPTIONAL: bool = false;
+    /// Whether the parameter takes every argument from its own on.
+    const VARIADIC: bool = false;
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self>;
+
+    /// Converts argument `index` of `args` after checking it against
+    /// `accept`, which is how `#[stardog_function]` binds each parameter.
+    /// Only types that take other than exactly one argument override it.
+    fn from_arguments(args: &'a Args<'_>, index: usize, accept: &Accept) -> Result<Self, Error> {
+        let position = args.position(index);
+        let term = args.term(index).ok_or_else(|| Error::new(ErrorCode::MissingArgument, format!("value[{}] is missing", position)))?;
+        accept.check(position, term, Self::TYPE)?;
+
+        Self::from_argument(term).ok_or_else(|| {
+            Error::new(
+                ErrorCode::TypeError,
+                format!("value[{}] ({}) cannot be converted to {}", position, term, std::any::type_name::<Self>()),
+            )
+        })
+    }
+}
+
+/// Conversion from a function's return value to the result term.
+///
+/// Structs with one field can derive it to return what that field would.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no RDF mapping, so it cannot be a result value",
+    label = "no conversion to an RDF term",
+    note = "results can be terms, strings, booleans, numbers, dates, durations or `LangString`s",
+    note = "a struct with one such field can `#[derive(IntoResult)]`"
+)]
+pub trait IntoResult {
+    /// The datatype IRI `describe` reports for the result, or [`ANY_TERM`].
+    const TYPE: &'static str;
+
+    fn into_result(self) -> Result<Term<'static>, Error>;
+}
+
+impl<'a> FromArgument<'a> for &'a Term<'a> {
+    const TYPE: &'static str = ANY_TERM;
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+        Some(term)
+    }
+}
+
+impl<'a> FromArgument<'a> for Term<'a> {
+    const TYPE: &'static str = ANY_TERM;
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+        Some(term.clone())
+    }
+}
+
+/// Takes quoted triples only.
+impl<'a> FromArgument<'a> for &'a Triple<'a> {
+    const TYPE: &'static str = TRIPLE;
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+        term.as_triple()
+    }
+}
+
+/// Takes quoted triples only.
+impl<'a> FromArgument<'a> for Triple<'a> {
+    const TYPE: &'static str = TRIPLE;
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+        term.as_triple().cloned()
+    }
+}
+
+/// Takes the IRI, blank node label or lexical form of any term but a quoted
+/// triple.
+impl<'a> FromArgument<'a> for &'a str {
+    const TYPE: &'static str = xsd!("string");
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+        term.value()
+    }
+}
+
+impl<'a> FromArgument<'a> for String {
+    const TYPE: &'static str = xsd!("string");
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+        term.value().map(str::to_owned)
+    }
+}
+
+/// Leaves a missing argument `None`. An argument that is there must still
+/// convert to `T`.
+impl<'a, T: FromArgument<'a>> FromArgument<'a> for Option<T> {
+    const TYPE: &'static str = T::TYPE;
+    const OPTIONAL: bool = true;
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+        T::from_argument(term).map(Some)
+    }
+
+    fn from_arguments(args: &'a Args<'_>, index: usize, accept: &Accept) -> Result<Self, Error> {
+        match args.term(index) {
+            Some(_) => T::from_arguments(args, index, accept).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Takes the argument and every one after it, each converted to `T`, so it
+/// is only useful as the last parameter.
+impl<'a, T: FromArgument<'a>> FromArgument<'a> for Vec<T> {
+    const TYPE: &'static str = T::TYPE;
+    const VARIADIC: bool = true;
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+        T::from_argument(term).map(|value| vec![value])
+    }
+
+    fn from_arguments(args: &'a Args<'_>, index: usize, accept: &Accept) -> Result<Self, Error> {
+        (index..args.len()).map(|index| T::from_arguments(args, index, accept)).collect()
+    }
+}
+
+impl<'a> FromArgument<'a> for LangString {
+    const TYPE: &'static str = RDF_LANG_STRING;
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+        if !term.is_literal() {
+            return None;
+        }
+
+        let value = term.value()?;
+        match term.lang() {
+            Some(lang) => Some(LangString::new(value, LanguageTag::parse(lang)?)),
+            None => Some(LangString::untagged(value)),
+        }
+    }
+}
+
+/// Takes the lexical form of a literal, as `lang()` returns it.
+impl<'a> FromArgument<'a> for LanguageTag {
+    const TYPE: &'static str = xsd!("language");
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+        LanguageTag::parse(term.value()?)
+    }
+}
+
+impl<'a> FromArgument<'a> for bool {
+    const TYPE: &'static str = xsd!("boolean");
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+        match term.value()? {
+            "true" | "1" => Some(true),
+            "false" | "0" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl IntoResult for Term<'_> {
+    const TYPE: &'static str = ANY_TERM;
+
+    fn into_result(self) -> Result<Term<'static>, Error> {
+        Ok(self.into_owned())
+    }
+}
+
+/// A quoted triple term.
+impl IntoResult for Triple<'_> {
+    const TYPE: &'static str = TRIPLE;
+
+    fn into_result(self) -> Result<Term<'static>, Error> {
+        Ok(Term::Triple(Box::new(self.into_owned())))
+    }
+}
+
+impl IntoResult for String {
+    const TYPE: &'static str = xsd!("string");
+
+    fn into_result(self) -> Result<Term<'static>, Error> {
+        Ok(Term::literal(self))
+    }
+}
+
+impl IntoResult for &str {
+    const TYPE: &'static str = xsd!("string");
+
+    fn into_result(self) -> Result<Term<'static>, Error> {
+        Ok(Term::literal(self.to_owned()))
+    }
+}
+
+impl IntoResult for LangString {
+    const TYPE: &'static str = RDF_LANG_STRING;
+
+    fn into_result(self) -> Result<Term<'static>, Error> {
+        match self.lang().map(LanguageTag::to_string) {
+            Some(lang) => Ok(Term::lang_literal(self.into_value(), lang)),
+            None => Ok(Term::literal(self.into_value())),
+        }
+    }
+}
+
+/// A simple literal, as `lang()` returns it.
+impl IntoResult for LanguageTag {
+    const TYPE: &'static str = xsd!("language");
+
+    fn into_result(self) -> Result<Term<'static>, Error> {
+        Ok(Term::literal(self.to_string()))
+    }
+}
+
+impl IntoResult for bool {
+    const TYPE: &'static str = xsd!("boolean");
+
+    fn into_result(self) -> Result<Term<'static>, Error> {
+        Ok(Term::typed_literal(self.to_string(), xsd!("boolean")))
+    }
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {
+        $(
+            /// Takes integers of any derived type, and the lexical form of
+            /// non-numeric literals.
+            impl<'a> FromArgument<'a> for $t {
+                const TYPE: &'static str = xsd!("integer");
+
+                fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+                    Integer::from_argument(term).and_then(|value| <$t>::try_from(&value).ok())
+                }
+            }
+
+            impl IntoResult for $t {
+                const TYPE: &'static str = xsd!("integer");
+
+                fn into_result(self) -> Result<Term<'static>, Error> {
+                    Ok(Term::typed_literal(self.to_string(), xsd!("integer")))
+                }
+            }
+        )*
+    };
+}
+
+integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+macro_rules! numeric {
+    ($t:ty, $datatype:literal, $rank:expr, $variant:ident, $into:expr) => {
+        /// Takes numeric literals that promote to the type, and the lexical
+        /// form of non-numeric literals.
+        impl<'a> FromArgument<'a> for $t {
+            const TYPE: &'static str = xsd!($datatype);
+
+            fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+                match numeric::argument(term, $rank)? {
+                    Numeric::$variant(value) => Some(value),
+                    _ => None,
+                }
+            }
+        }
+
+        impl IntoResult for $t {
+            const TYPE: &'static str = xsd!($datatype);
+
+            fn into_result(self) -> Result<Term<'static>, Error> {
+                Ok(Term::typed_literal($into(self), xsd!($datatype)))
+            }
+        }
+    };
+}
+
+numeric!(Integer, "integer", 0, Integer, |value: Integer| value.to_string());
+numeric!(Decimal, "decimal", 1, Decimal, |value: Decimal| value.to_string());
+numeric!(f32, "float", 2, Float, canonical_float);
+numeric!(f64, "double", 3, Double, canonical_double);
+
+/// Takes a literal of any numeric datatype, or a number written as in a
+/// query (`1`, `1.5`, `1.5e0`) in any other literal.
+impl<'a> FromArgument<'a> for Numeric {
+    const TYPE: &'static str = NUMERIC;
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+        Numeric::from_term(term).or_else(|| Numeric::parse_untyped(term.value()?))
+    }
+}
+
+impl IntoResult for Numeric {
+    const TYPE: &'static str = NUMERIC;
+
+    fn into_result(self) -> Result<Term<'static>, Error> {
+        Ok(self.into_term())
+    }
+}
+
+macro_rules! temporal {
+    ($t:ty, $datatype:literal, $($accepted:literal),*) => {
+        /// Takes literals of the type, and the lexical form of literals that
+        /// are not dates, times or durations.
+        impl<'a> FromArgument<'a> for $t {
+            const TYPE: &'static str = xsd!($datatype);
+
+            fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+                match term.datatype() {
+                    Some(datatype) if is_temporal(datatype) && ![$(xsd!($accepted)),*].contains(&datatype) => None,
+                    _ => <$t>::parse(term.value()?),
+                }
+            }
+        }
+
+        impl IntoResult for $t {
+            const TYPE: &'static str = xsd!($datatype);
+
+            fn into_result(self) -> Result<Term<'static>, Error> {
+                Ok(Term::typed_literal(self.to_string(), xsd!($datatype)))
+            }
+        }
+    };
+}
+
+temporal!(DateTime, "dateTime", "dateTime", "dateTimeStamp");
+temporal!(Date, "date", "date");
+temporal!(Time, "time", "time");
+temporal!(GYear, "gYear", "gYear");
+
+/// Takes literals of any of the duration datatypes, and the lexical form of
+/// literals that are not dates, times or durations.
+impl<'a> FromArgument<'a> for Duration {
+    const TYPE: &'static str = xsd!("duration");
+
+    fn from_argument(term: &'a Term<'a>) -> Option<Self> {
+        match term.datatype() {
+            Some(xsd!("dayTimeDuration")) => Duration::parse_day_time(term.value()?),
+            Some(xsd!("yearMonthDuration")) => Duration::parse_year_month(term.value()?),
+            Some(datatype) if is_temporal(datatype) && datatype != xsd!("duration") => None,
+            _ => Duration::parse(term.value()?),
+        }
+    }
+}
+
+/// Typed as the most specific of `xsd:dayTimeDuration`,
+/// `xsd:yearMonthDuration` and `xsd:duration`.
+impl IntoResult for Duration {
+    const TYPE: &'static str = xsd!("duration");
+
+    fn into_result(self) -> Result<Term<'static>, Error> {
+        Ok(Term::typed_literal(self.to_string(), self.datatype()))
+    }
+}
@@ -0,0 +1,888 @@
+//! XSD's date and time datatypes: `xsd:dateTime`, `xsd:date`, `xsd:time`,
+//! `xsd:gYear` and the durations, with timezone offsets and the ordering and
+//! arithmetic of XPath's `op:*` functions.
+//!
+//! Nothing here reads a clock, so values without a timezone are compared and
+//! subtracted as if they were in UTC (SPARQL leaves the implicit timezone to
+//! the implementation). Fractional seconds are kept to the nanosecond;
+//! further digits are truncated.
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+
+use crate::XSD;
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+const NANOS_PER_MINUTE: i128 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_DAY: i128 = 24 * 60 * NANOS_PER_MINUTE;
+
+/// How far timezones can be from UTC, in minutes. Values without one are
+/// anywhere within this of the same value in UTC.
+const MAX_OFFSET: i16 = 14 * 60;
+
+/// Whether `datatype` is one of XSD's date, time or duration datatypes.
+pub(crate) fn is_temporal(datatype: &str) -> bool {
+    matches!(
+        datatype.strip_prefix(XSD),
+        Some(
+            "dateTime" | "dateTimeStamp" | "date" | "time" | "gYear" | "gYearMonth" | "gMonth" | "gMonthDay" | "gDay" | "duration" | "dayTimeDuration" | "yearMonthDuration"
+        )
+    )
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// The number of days in `month` of `year`.
+pub fn days_in_month(year: i64, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date, where the year
+/// before 1 is 0. In `i128`, as are the nanoseconds of any dateTime, so no
+/// year overflows it.
+fn days_from_civil(year: i64, month: u8, day: u8) -> i128 {
+    let (year, month, day) = (year as i128, month as i128, day as i128);
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The date `days` days after 1970-01-01, if its year fits an `i64`.
+fn civil_from_days(days: i128) -> Option<(i64, u8, u8)> {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+
+    let year = i64::try_from(year_of_era + era * 400 + if month <= 2 { 1 } else { 0 }).ok()?;
+
+    Some((year, month as u8, day as u8))
+}
+
+/// A timezone offset from UTC, between -14:00 and +14:00.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Timezone {
+    minutes: i16,
+}
+
+impl Timezone {
+    pub const UTC: Timezone = Timezone { minutes: 0 };
+
+    /// The timezone `minutes` east of UTC, if that is within 14 hours.
+    pub fn from_minutes(minutes: i16) -> Option<Timezone> {
+        Some(Timezone { minutes }).filter(|_| minutes.abs() <= MAX_OFFSET)
+    }
+
+    pub fn minutes(self) -> i16 {
+        self.minutes
+    }
+
+    fn parse(lexical: &str) -> Option<Timezone> {
+        if lexical == "Z" {
+            return Some(Timezone::UTC);
+        }
+        let (sign, rest) = match lexical.as_bytes().first()? {
+            b'+' => (1, &lexical[1..]),
+            b'-' => (-1, &lexical[1..]),
+            _ => return None,
+        };
+        let (hours, minutes) = rest.split_once(':')?;
+        let (hours, minutes) = (two_digits(hours)?, two_digits(minutes)?);
+        if minutes > 59 {
+            return None;
+        }
+
+        Timezone::from_minutes(sign * (hours as i16 * 60 + minutes as i16))
+    }
+}
+
+/// `Z`, or the offset as `+hh:mm`/`-hh:mm`.
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.minutes == 0 {
+            return f.write_str("Z");
+        }
+
+        let sign = if self.minutes < 0 { '-' } else { '+' };
+        write!(f, "{}{:02}:{:02}", sign, self.minutes.abs() / 60, self.minutes.abs() % 60)
+    }
+}
+
+fn two_digits(lexical: &str) -> Option<u8> {
+    Some(lexical).filter(|lexical| lexical.len() == 2 && lexical.bytes().all(|b| b.is_ascii_digit()))?.parse().ok()
+}
+
+/// Splits `lexical` after its XSD whitespace is stripped into what comes
+/// before its timezone and the timezone, if it has a valid one.
+fn split_timezone(lexical: &str) -> Option<(&str, Option<Timezone>)> {
+    let lexical = lexical.trim_matches(|c| matches!(c, ' ' | '\t' | '\n' | '\r'));
+    if let Some(rest) = lexical.strip_suffix('Z') {
+        return Some((rest, Some(Timezone::UTC)));
+    }
+    // `+hh:mm` or `-hh:mm`; a `-` anywhere else belongs to the value
+    match lexical.len().checked_sub(6).and_then(|at| lexical.get(at..)) {
+        Some(offset) if offset.starts_with(['+', '-']) && offset.as_bytes()[3] == b':' => {
+            Some((&lexical[..lexical.len() - 6], Some(Timezone::parse(offset)?)))
+        }
+        _ => Some((lexical, None)),
+    }
+}
+
+/// Reads a year: an optional `-` and at least four digits, with no leading
+/// zero beyond four.
+fn parse_year(lexical: &str) -> Option<i64> {
+    let digits = lexical.strip_prefix('-').unwrap_or(lexical);
+    if digits.len() < 4 || (digits.len() > 4 && digits.starts_with('0')) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let year: i64 = digits.parse().ok()?;
+
+    Some(if lexical.starts_with('-') { -year } else { year })
+}
+
+/// Reads `yyyy-mm-dd`.
+fn parse_date(lexical: &str) -> Option<(i64, u8, u8)> {
+    // the year may itself start with a `-`
+    let day_at = lexical.rfind('-')?;
+    let month_at = lexical[..day_at].rfind('-')?;
+    let (year, month, day) = (parse_year(&lexical[..month_at])?, two_digits(&lexical[month_at + 1..day_at])?, two_digits(&lexical[day_at + 1..])?);
+
+    Some((year, month, day)).filter(|_| (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month))
+}
+
+/// Reads `hh:mm:ss` with optional fractional seconds, allowing `24:00:00` for
+/// the end of the day.
+fn parse_time(lexical: &str) -> Option<(u8, u8, u8, u32)> {
+    let mut parts = lexical.splitn(3, ':');
+    let (hour, minute) = (two_digits(parts.next()?)?, two_digits(parts.next()?)?);
+    let (second, nanosecond) = parse_seconds(parts.next()?)?;
+    if second.len() != 2 || minute > 59 || second.parse::<u8>().ok()? > 59 || hour > 24 || (hour == 24 && (minute, &*second, nanosecond) != (0, "00", 0)) {
+        return None;
+    }
+
+    Some((hour, minute, second.parse().ok()?, nanosecond))
+}
+
+/// Splits `ss.sss` into its whole seconds, as written, and nanoseconds.
+fn parse_seconds(lexical: &str) -> Option<(String, u32)> {
+    let (whole, fraction) = match lexical.split_once('.') {
+        Some((whole, fraction)) if !fraction.is_empty() => (whole, fraction),
+        Some(_) => return None,
+        None => (lexical, ""),
+    };
+    if whole.is_empty() || !whole.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let nanosecond = format!("{:0<9}", &fraction[..fraction.len().min(9)]).parse().ok()?;
+
+    Some((whole.to_owned(), nanosecond))
+}
+
+fn write_year(f: &mut fmt::Formatter, year: i64) -> fmt::Result {
+    if year < 0 {
+        f.write_str("-")?;
+    }
+    write!(f, "{:04}", year.unsigned_abs())
+}
+
+/// Seconds with only the fractional digits that are not zero.
+fn write_seconds(f: &mut fmt::Formatter, second: u8, nanosecond: u32) -> fmt::Result {
+    write!(f, "{:02}", second)?;
+    if nanosecond != 0 {
+        write!(f, ".{}", format!("{:09}", nanosecond).trim_end_matches('0'))?;
+    }
+    Ok(())
+}
+
+fn write_timezone(f: &mut fmt::Formatter, timezone: Option<Timezone>) -> fmt::Result {
+    timezone.map_or(Ok(()), |timezone| write!(f, "{}", timezone))
+}
+
+/// Orders instants, in nanoseconds since the epoch in UTC, of values that
+/// might not have a timezone: one without is anywhere within 14 hours of
+/// where it would be in UTC, so is only before or after one with a timezone
+/// that is further away than that.
+fn compare_instants(a: i128, a_timezone: Option<Timezone>, b: i128, b_timezone: Option<Timezone>) -> Option<Ordering> {
+    let slack = MAX_OFFSET as i128 * NANOS_PER_MINUTE;
+    match (a_timezone.is_some(), b_timezone.is_some()) {
+        (true, false) => compare_instants(b, b_timezone, a, a_timezone).map(Ordering::reverse),
+        (false, true) if a + slack < b => Some(Ordering::Less),
+        (false, true) if a - slack > b => Some(Ordering::Greater),
+        (false, true) => None,
+        _ => Some(a.cmp(&b)),
+    }
+}
+
+/// An `xsd:dateTime`.
+#[derive(Clone, Copy, Debug)]
+pub struct DateTime {
+    year: i64,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+    timezone: Option<Timezone>,
+}
+
+impl DateTime {
+    /// The dateTime with these fields, if they are valid.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(year: i64, month: u8, day: u8, hour: u8, minute: u8, second: u8, nanosecond: u32, timezone: Option<Timezone>) -> Option<DateTime> {
+        let valid = (1..=12).contains(&month)
+            && day >= 1
+            && day <= days_in_month(year, month)
+            && hour < 24
+            && minute < 60
+            && second < 60
+            && nanosecond < NANOS_PER_SECOND as u32;
+
+        Some(DateTime { year, month, day, hour, minute, second, nanosecond, timezone }).filter(|_| valid)
+    }
+
+    /// Reads `yyyy-mm-ddThh:mm:ss(.s+)?` with an optional timezone.
+    pub fn parse(lexical: &str) -> Option<DateTime> {
+        let (lexical, timezone) = split_timezone(lexical)?;
+        let (date, time) = lexical.split_once('T')?;
+        let (year, month, day) = parse_date(date)?;
+        let (hour, minute, second, nanosecond) = parse_time(time)?;
+
+        if hour == 24 {
+            let (year, month, day) = civil_from_days(days_from_civil(year, month, day) + 1)?;
+            return DateTime::new(year, month, day, 0, 0, 0, 0, timezone);
+        }
+        DateTime::new(year, month, day, hour, minute, second, nanosecond, timezone)
+    }
+
+    /// Nanoseconds since 1970-01-01T00:00:00 in the dateTime's own timezone.
+    fn local_nanos(&self) -> i128 {
+        let seconds = (self.hour as i128 * 60 + self.minute as i128) * 60 + self.second as i128;
+        days_from_civil(self.year, self.month, self.day) * NANOS_PER_DAY + seconds * NANOS_PER_SECOND + self.nanosecond as i128
+    }
+
+    fn from_local_nanos(nanos: i128, timezone: Option<Timezone>) -> Option<DateTime> {
+        let (days, nanos) = (nanos.div_euclid(NANOS_PER_DAY), nanos.rem_euclid(NANOS_PER_DAY));
+        let (year, month, day) = civil_from_days(days)?;
+        let seconds = nanos / NANOS_PER_SECOND;
+
+        Some(DateTime {
+            year,
+            month,
+            day,
+            hour: (seconds / 3600) as u8,
+            minute: (seconds / 60 % 60) as u8,
+            second: (seconds % 60) as u8,
+            nanosecond: (nanos % NANOS_PER_SECOND) as u32,
+            timezone,
+        })
+    }
+
+    /// Nanoseconds since the epoch, taking a missing timezone as UTC.
+    fn instant(&self) -> i128 {
+        self.local_nanos() - self.timezone.map_or(0, |timezone| timezone.minutes as i128) * NANOS_PER_MINUTE
+    }
+
+    pub fn year(&self) -> i64 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    pub fn timezone(&self) -> Option<Timezone> {
+        self.timezone
+    }
+
+    pub fn date(&self) -> Date {
+        Date { year: self.year, month: self.month, day: self.day, timezone: self.timezone }
+    }
+
+    pub fn time(&self) -> Time {
+        Time { hour: self.hour, minute: self.minute, second: self.second, nanosecond: self.nanosecond, timezone: self.timezone }
+    }
+
+    /// The same instant in `timezone`, or, for a dateTime without one, the
+    /// same local time in it. `None` if that is a day past the last year.
+    pub fn with_timezone(&self, timezone: Timezone) -> Option<DateTime> {
+        match self.timezone {
+            Some(_) => DateTime::from_local_nanos(self.instant() + timezone.minutes as i128 * NANOS_PER_MINUTE, Some(timezone)),
+            None => Some(DateTime { timezone: Some(timezone), ..*self }),
+        }
+    }
+
+    /// Adds the months first, keeping the day within the month they end up
+    /// in (so a month after January 31st is the last day of February), then
+    /// the rest. `None` if the year of the result does not fit an `i64`.
+    pub fn checked_add(self, duration: Duration) -> Option<DateTime> {
+        self.add_parts(duration.months as i128, duration.nanos)
+    }
+
+    pub fn checked_sub(self, duration: Duration) -> Option<DateTime> {
+        self.add_parts(-(duration.months as i128), duration.nanos.checked_neg()?)
+    }
+
+    fn add_parts(self, months: i128, nanos: i128) -> Option<DateTime> {
+        let months = self.year as i128 * 12 + (self.month as i128 - 1) + months;
+        let (year, month) = (i64::try_from(months.div_euclid(12)).ok()?, (months.rem_euclid(12) + 1) as u8);
+        let day = self.day.min(days_in_month(year, month));
+        let moved = DateTime { year, month, day, ..self };
+
+        DateTime::from_local_nanos(moved.local_nanos().checked_add(nanos)?, self.timezone)
+    }
+
+    /// The local time without its timezone.
+    pub fn without_timezone(&self) -> DateTime {
+        DateTime { timezone: None, ..*self }
+    }
+}
+
+/// The same instant, even when written in different timezones.
+impl PartialEq for DateTime {
+    fn eq(&self, other: &DateTime) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+/// Only partial, as a dateTime without a timezone is neither before nor after
+/// one with a timezone less than 14 hours away.
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &DateTime) -> Option<Ordering> {
+        compare_instants(self.instant(), self.timezone, other.instant(), other.timezone)
+    }
+}
+
+/// [`DateTime::checked_add`], panicking if the year overflows.
+impl Add<Duration> for DateTime {
+    type Output = DateTime;
+
+    fn add(self, duration: Duration) -> DateTime {
+        self.checked_add(duration).expect("overflow when adding a duration to a dateTime")
+    }
+}
+
+impl Sub<Duration> for DateTime {
+    type Output = DateTime;
+
+    fn sub(self, duration: Duration) -> DateTime {
+        self.checked_sub(duration).expect("overflow when subtracting a duration from a dateTime")
+    }
+}
+
+/// The `xsd:dayTimeDuration` between two instants.
+impl Sub for DateTime {
+    type Output = Duration;
+
+    fn sub(self, other: DateTime) -> Duration {
+        Duration { months: 0, nanos: self.instant() - other.instant() }
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}T{:02}:{:02}:", self.date().without_timezone(), self.hour, self.minute)?;
+        write_seconds(f, self.second, self.nanosecond)?;
+        write_timezone(f, self.timezone)
+    }
+}
+
+/// An `xsd:date`: a day, starting at midnight in its timezone.
+#[derive(Clone, Copy, Debug)]
+pub struct Date {
+    year: i64,
+    month: u8,
+    day: u8,
+    timezone: Option<Timezone>,
+}
+
+impl Date {
+    /// The date with these fields, if they are valid.
+    pub fn new(year: i64, month: u8, day: u8, timezone: Option<Timezone>) -> Option<Date> {
+        DateTime::new(year, month, day, 0, 0, 0, 0, timezone).map(|start| start.date())
+    }
+
+    /// Reads `yyyy-mm-dd` with an optional timezone.
+    pub fn parse(lexical: &str) -> Option<Date> {
+        let (lexical, timezone) = split_timezone(lexical)?;
+        let (year, month, day) = parse_date(lexical)?;
+
+        Date::new(year, month, day, timezone)
+    }
+
+    pub fn year(&self) -> i64 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn timezone(&self) -> Option<Timezone> {
+        self.timezone
+    }
+
+    /// Midnight at the start of the day.
+    pub fn start(&self) -> DateTime {
+        DateTime { year: self.year, month: self.month, day: self.day, hour: 0, minute: 0, second: 0, nanosecond: 0, timezone: self.timezone }
+    }
+
+    pub fn without_timezone(&self) -> Date {
+        Date { timezone: None, ..*self }
+    }
+
+    /// Adds the duration to the start of the day, dropping any time of day it
+    /// ends up at. `None` if the year of the result does not fit an `i64`.
+    pub fn checked_add(self, duration: Duration) -> Option<Date> {
+        self.start().checked_add(duration).map(|end| end.date())
+    }
+
+    pub fn checked_sub(self, duration: Duration) -> Option<Date> {
+        self.start().checked_sub(duration).map(|end| end.date())
+    }
+}
+
+impl PartialEq for Date {
+    fn eq(&self, other: &Date) -> bool {
+        self.start() == other.start()
+    }
+}
+
+impl PartialOrd for Date {
+    fn partial_cmp(&self, other: &Date) -> Option<Ordering> {
+        self.start().partial_cmp(&other.start())
+    }
+}
+
+/// [`Date::checked_add`], panicking if the year overflows.
+impl Add<Duration> for Date {
+    type Output = Date;
+
+    fn add(self, duration: Duration) -> Date {
+        self.checked_add(duration).expect("overflow when adding a duration to a date")
+    }
+}
+
+impl Sub<Duration> for Date {
+    type Output = Date;
+
+    fn sub(self, duration: Duration) -> Date {
+        self.checked_sub(duration).expect("overflow when subtracting a duration from a date")
+    }
+}
+
+impl Sub for Date {
+    type Output = Duration;
+
+    fn sub(self, other: Date) -> Duration {
+        self.start() - other.start()
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_year(f, self.year)?;
+        write!(f, "-{:02}-{:02}", self.month, self.day)?;
+        write_timezone(f, self.timezone)
+    }
+}
+
+/// An `xsd:time`: a time of any day.
+#[derive(Clone, Copy, Debug)]
+pub struct Time {
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+    timezone: Option<Timezone>,
+}
+
+impl Time {
+    /// The time with these fields, if they are valid.
+    pub fn new(hour: u8, minute: u8, second: u8, nanosecond: u32, timezone: Option<Timezone>) -> Option<Time> {
+        DateTime::new(1972, 12, 31, hour, minute, second, nanosecond, timezone).map(|on| on.time())
+    }
+
+    /// Reads `hh:mm:ss(.s+)?` with an optional timezone.
+    pub fn parse(lexical: &str) -> Option<Time> {
+        let (lexical, timezone) = split_timezone(lexical)?;
+        let (hour, minute, second, nanosecond) = parse_time(lexical)?;
+
+        Time::new(hour % 24, minute, second, nanosecond, timezone)
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    pub fn timezone(&self) -> Option<Timezone> {
+        self.timezone
+    }
+
+    /// The time on the day XPath compares times on.
+    fn on_reference_day(&self) -> DateTime {
+        Date { year: 1972, month: 12, day: 31, timezone: self.timezone }.start() + Duration::from_nanos(self.nanos_of_day())
+    }
+
+    fn nanos_of_day(&self) -> i128 {
+        ((self.hour as i128 * 60 + self.minute as i128) * 60 + self.second as i128) * NANOS_PER_SECOND + self.nanosecond as i128
+    }
+
+    /// The time `nanos` later, wrapping around midnight.
+    fn add_nanos(self, nanos: i128) -> Time {
+        let nanos = (self.nanos_of_day() + nanos).rem_euclid(NANOS_PER_DAY);
+        DateTime::from_local_nanos(nanos, self.timezone).expect("1970 is a valid year").time()
+    }
+}
+
+impl PartialEq for Time {
+    fn eq(&self, other: &Time) -> bool {
+        self.on_reference_day() == other.on_reference_day()
+    }
+}
+
+impl PartialOrd for Time {
+    fn partial_cmp(&self, other: &Time) -> Option<Ordering> {
+        self.on_reference_day().partial_cmp(&other.on_reference_day())
+    }
+}
+
+/// Adds the day and time part of the duration, wrapping around midnight;
+/// months make no difference to a time.
+impl Add<Duration> for Time {
+    type Output = Time;
+
+    fn add(self, duration: Duration) -> Time {
+        self.add_nanos(duration.nanos.rem_euclid(NANOS_PER_DAY))
+    }
+}
+
+impl Sub<Duration> for Time {
+    type Output = Time;
+
+    fn sub(self, duration: Duration) -> Time {
+        self.add_nanos(NANOS_PER_DAY - duration.nanos.rem_euclid(NANOS_PER_DAY))
+    }
+}
+
+impl Sub for Time {
+    type Output = Duration;
+
+    fn sub(self, other: Time) -> Duration {
+        self.on_reference_day() - other.on_reference_day()
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}:", self.hour, self.minute)?;
+        write_seconds(f, self.second, self.nanosecond)?;
+        write_timezone(f, self.timezone)
+    }
+}
+
+/// An `xsd:gYear`: a whole year, starting on January 1st.
+#[derive(Clone, Copy, Debug)]
+pub struct GYear {
+    year: i64,
+    timezone: Option<Timezone>,
+}
+
+impl GYear {
+    pub fn new(year: i64, timezone: Option<Timezone>) -> GYear {
+        GYear { year, timezone }
+    }
+
+    /// Reads `yyyy` with an optional timezone.
+    pub fn parse(lexical: &str) -> Option<GYear> {
+        let (lexical, timezone) = split_timezone(lexical)?;
+        Some(GYear::new(parse_year(lexical)?, timezone))
+    }
+
+    pub fn year(&self) -> i64 {
+        self.year
+    }
+
+    pub fn timezone(&self) -> Option<Timezone> {
+        self.timezone
+    }
+
+    fn start(&self) -> DateTime {
+        Date { year: self.year, month: 1, day: 1, timezone: self.timezone }.start()
+    }
+}
+
+impl PartialEq for GYear {
+    fn eq(&self, other: &GYear) -> bool {
+        self.start() == other.start()
+    }
+}
+
+impl PartialOrd for GYear {
+    fn partial_cmp(&self, other: &GYear) -> Option<Ordering> {
+        self.start().partial_cmp(&other.start())
+    }
+}
+
+impl fmt::Display for GYear {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_year(f, self.year)?;
+        write_timezone(f, self.timezone)
+    }
+}
+
+/// An `xsd:duration`: a number of months and a number of nanoseconds, both
+/// negative or neither. `xsd:yearMonthDuration`s have no nanoseconds and
+/// `xsd:dayTimeDuration`s no months.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Duration {
+    months: i64,
+    nanos: i128,
+}
+
+impl Duration {
+    /// The duration of `months` and `nanos`, unless their signs differ.
+    pub fn new(months: i64, nanos: i128) -> Option<Duration> {
+        Some(Duration { months, nanos }).filter(|_| (months >= 0 && nanos >= 0) || (months <= 0 && nanos <= 0))
+    }
+
+    pub fn from_months(months: i64) -> Duration {
+        Duration { months, nanos: 0 }
+    }
+
+    pub fn from_days(days: i64) -> Duration {
+        Duration { months: 0, nanos: days as i128 * NANOS_PER_DAY }
+    }
+
+    pub fn from_seconds(seconds: i64) -> Duration {
+        Duration { months: 0, nanos: seconds as i128 * NANOS_PER_SECOND }
+    }
+
+    pub fn from_nanos(nanos: i128) -> Duration {
+        Duration { months: 0, nanos }
+    }
+
+    /// Reads `-?PnYnMnDTnHnMn.nS`, where every part is optional but at least
+    /// one must be there, and `T` only comes before a time part.
+    pub fn parse(lexical: &str) -> Option<Duration> {
+        let lexical = lexical.trim_matches(|c| matches!(c, ' ' | '\t' | '\n' | '\r'));
+        let (negative, lexical) = match lexical.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, lexical),
+        };
+        let (date, time) = match lexical.strip_prefix('P')?.split_once('T') {
+            Some((_, "")) => return None,
+            Some((date, time)) => (date, time),
+            None => (lexical.strip_prefix('P')?, ""),
+        };
+        if date.is_empty() && time.is_empty() {
+            return None;
+        }
+
+        let mut months = 0i64;
+        let mut nanos = 0i128;
+        for (part, designators) in [(date, "YMD"), (time, "HMS")] {
+            let mut allowed = designators;
+            let mut rest = part;
+            while !rest.is_empty() {
+                let at = rest.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+                let (number, designator) = (&rest[..at], rest[at..].chars().next()?);
+                allowed = &allowed[allowed.find(designator)? + 1..];
+                rest = &rest[at + 1..];
+
+                if designator == 'S' {
+                    let (seconds, nanosecond) = parse_seconds(number)?;
+                    nanos = nanos.checked_add(seconds.parse::<i128>().ok()?.checked_mul(NANOS_PER_SECOND)? + nanosecond as i128)?;
+                    continue;
+                }
+                if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                let number: i128 = number.parse().ok()?;
+                match (designators, designator) {
+                    (_, 'Y') => months = months.checked_add(i64::try_from(number.checked_mul(12)?).ok()?)?,
+                    ("YMD", 'M') => months = months.checked_add(i64::try_from(number).ok()?)?,
+                    (_, 'D') => nanos = nanos.checked_add(number.checked_mul(NANOS_PER_DAY)?)?,
+                    (_, 'H') => nanos = nanos.checked_add(number.checked_mul(60 * NANOS_PER_MINUTE)?)?,
+                    _ => nanos = nanos.checked_add(number.checked_mul(NANOS_PER_MINUTE)?)?,
+                }
+            }
+        }
+
+        Some(if negative { -Duration { months, nanos } } else { Duration { months, nanos } })
+    }
+
+    /// Reads an `xsd:dayTimeDuration`, which has no years or months.
+    pub fn parse_day_time(lexical: &str) -> Option<Duration> {
+        let date = lexical.split('T').next()?;
+        Duration::parse(lexical).filter(|_| !date.contains(['Y', 'M']))
+    }
+
+    /// Reads an `xsd:yearMonthDuration`, which has only years and months.
+    pub fn parse_year_month(lexical: &str) -> Option<Duration> {
+        Duration::parse(lexical).filter(|_| !lexical.contains(['D', 'T']))
+    }
+
+    pub fn months(&self) -> i64 {
+        self.months
+    }
+
+    /// The day and time part, in nanoseconds.
+    pub fn nanos(&self) -> i128 {
+        self.nanos
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.months < 0 || self.nanos < 0
+    }
+
+    /// `self + other`, unless the result would have months and nanoseconds
+    /// of different signs.
+    pub fn checked_add(self, other: Duration) -> Option<Duration> {
+        Duration::new(self.months.checked_add(other.months)?, self.nanos.checked_add(other.nanos)?)
+    }
+
+    pub fn checked_sub(self, other: Duration) -> Option<Duration> {
+        Duration::new(self.months.checked_sub(other.months)?, self.nanos.checked_sub(other.nanos)?)
+    }
+
+    /// The most specific of the duration datatypes the duration is a value
+    /// of, preferring `xsd:dayTimeDuration` for a zero duration.
+    pub fn datatype(&self) -> &'static str {
+        match (self.months, self.nanos) {
+            (0, _) => concat!("http://www.w3.org/2001/XMLSchema#", "dayTimeDuration"),
+            (_, 0) => concat!("http://www.w3.org/2001/XMLSchema#", "yearMonthDuration"),
+            _ => concat!("http://www.w3.org/2001/XMLSchema#", "duration"),
+        }
+    }
+}
+
+impl Neg for Duration {
+    type Output = Duration;
+
+    fn neg(self) -> Duration {
+        Duration { months: -self.months, nanos: -self.nanos }
+    }
+}
+
+/// Durations with only months, or only days and times, are totally ordered.
+/// Others are ordered the way XSD does it, by adding them to four dateTimes
+/// chosen for their different month lengths, and are incomparable if those
+/// disagree (`P1M` against `P30D`, say).
+impl PartialOrd for Duration {
+    fn partial_cmp(&self, other: &Duration) -> Option<Ordering> {
+        if self.months == other.months || self.nanos == other.nanos {
+            return Some(self.months.cmp(&other.months).then(self.nanos.cmp(&other.nanos)));
+        }
+
+        // durations too long to add to a date are incomparable
+        let orderings: Option<Vec<Ordering>> = [(1696, 9), (1697, 2), (1903, 3), (1903, 7)]
+            .iter()
+            .map(|&(year, month)| {
+                let start = Date { year, month, day: 1, timezone: Some(Timezone::UTC) }.start();
+                Some(start.checked_add(*self)?.instant().cmp(&start.checked_add(*other)?.instant()))
+            })
+            .collect();
+        let orderings = orderings?;
+
+        Some(orderings[0]).filter(|first| orderings.iter().all(|ordering| ordering == first))
+    }
+}
+
+/// The canonical form: only the parts that are not zero, with years and
+/// months, and days, hours, minutes and seconds carried into each other, as
+/// in `P1Y2M`, `-PT36H` and `P1DT0.5S`. A zero duration is `PT0S`.
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.months == 0 && self.nanos == 0 {
+            return f.write_str("PT0S");
+        }
+        if self.is_negative() {
+            f.write_str("-")?;
+        }
+        f.write_str("P")?;
+
+        let (years, months) = (self.months.unsigned_abs() / 12, self.months.unsigned_abs() % 12);
+        let nanos = self.nanos.unsigned_abs();
+        let (days, nanos) = (nanos / NANOS_PER_DAY as u128, nanos % NANOS_PER_DAY as u128);
+        let (hours, nanos) = (nanos / (60 * NANOS_PER_MINUTE) as u128, nanos % (60 * NANOS_PER_MINUTE) as u128);
+        let (minutes, nanos) = (nanos / NANOS_PER_MINUTE as u128, nanos % NANOS_PER_MINUTE as u128);
+        let (seconds, nanos) = (nanos / NANOS_PER_SECOND as u128, nanos % NANOS_PER_SECOND as u128);
+
+        for (value, designator) in [(years as u128, 'Y'), (months as u128, 'M'), (days, 'D')] {
+            if value != 0 {
+                write!(f, "{}{}", value, designator)?;
+            }
+        }
+        if hours != 0 || minutes != 0 || seconds != 0 || nanos != 0 {
+            f.write_str("T")?;
+            for (value, designator) in [(hours, 'H'), (minutes, 'M')] {
+                if value != 0 {
+                    write!(f, "{}{}", value, designator)?;
+                }
+            }
+            if seconds != 0 || nanos != 0 {
+                write!(f, "{}", seconds)?;
+                if nanos != 0 {
+                    write!(f, ".{}", format!("{:09}", nanos).trim_end_matches('0'))?;
+                }
+                f.write_str("S")?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// What a module's `describe` export returns, as JSON.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleDescription {
+    pub name: String,
+    pub version: String,
+    pub license: String,
+    pub description: String,
+    pub functions: Vec<FunctionDescription>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aggregates: Vec<AggregateDescription>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tables: Vec<TableDescription>,
+}
+
+/// One function of a module, generated by `#[stardog_function]` from its
+/// signature and doc comment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionDescription {
+    pub name: String,
+    pub description: String,
+    /// Whether the same arguments always give the same result.
+    pub deterministic: bool,
+    pub parameters: Vec<Parameter>,
+    /// See [`Parameter::kind`].
+    pub returns: String,
+}
+
+/// One [`Aggregate`](crate::Aggregate) of a module.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregateDescription {
+    pub name: String,
+    pub description: String,
+}
+
+/// One [`TableFunction`](crate::TableFunction) of a module.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableDescription {
+    pub name: String,
+    pub description: String,
+    /// The variables of every row, empty if they are the default ones.
+    pub vars: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Parameter {
+    pub name: String,
+    /// The datatype IRI of the literal the parameter takes, or `term` if it
+    /// takes any RDF term.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Whether the argument may be left out.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub optional: bool,
+    /// Whether the parameter takes every argument from its own on.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub variadic: bool,
+}
+
+fn is_false(flag: &bool) -> bool {
+    !flag
+}
+
+impl Parameter {
+    pub fn new<N: Into<String>, K: Into<String>>(name: N, kind: K) -> Parameter {
+        Parameter { name: name.into(), kind: kind.into(), optional: false, variadic: false }
+    }
+}
@@ -0,0 +1,94 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// Why an evaluation failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorCode {
+    /// The input buffer was not UTF-8 or not a SPARQL JSON document.
+    InvalidInput,
+    /// The module exports no function by the requested name.
+    UnknownFunction,
+    /// Fewer arguments were passed than the function takes.
+    MissingArgument,
+    /// An argument could not be converted to the parameter's type.
+    TypeError,
+    /// The function itself reported a failure.
+    Failed,
+    /// The function needs the state `init` sets up, which it has not been
+    /// called for (or was called with a config for other functions).
+    NotInitialized,
+}
+
+const CODES: &[(ErrorCode, &str)] = &[
+    (ErrorCode::InvalidInput, "invalid-input"),
+    (ErrorCode::UnknownFunction, "unknown-function"),
+    (ErrorCode::MissingArgument, "missing-argument"),
+    (ErrorCode::TypeError, "type-error"),
+    (ErrorCode::Failed, "failed"),
+    (ErrorCode::NotInitialized, "not-initialized"),
+];
+
+impl ErrorCode {
+    /// The name the code is serialized as.
+    pub fn as_str(self) -> &'static str {
+        CODES.iter().find(|(code, _)| *code == self).map(|(_, name)| *name).expect("every code is named")
+    }
+}
+
+impl FromStr for ErrorCode {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<ErrorCode, ()> {
+        CODES.iter().find(|(_, candidate)| *candidate == name).map(|(code, _)| *code).ok_or(())
+    }
+}
+
+/// The error half of an evaluation result.
+///
+/// It is returned to the host in place of the result row, as a SPARQL JSON
+/// document with no bindings and a top-level `error` member:
+///
+/// ```json
+/// {"head": {"vars": []}, "results": {"bindings": []},
+///  "error": {"code": "type-error", "message": "..."}}
+/// ```
+///
+/// A host that does not know about the `error` member still sees an empty
+/// result and treats the row as an error.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Error {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new<S: Into<String>>(code: ErrorCode, message: S) -> Error {
+        Error { code, message: message.into() }
+    }
+
+    pub fn failed<S: Into<String>>(message: S) -> Error {
+        Error::new(ErrorCode::Failed, message)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<String> for Error {
+    fn from(message: String) -> Error {
+        Error::failed(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Error {
+        Error::failed(message)
+    }
+}
@@ -0,0 +1,199 @@
+//! Language tags, as in `"straße"@de`: BCP 47 parsing, canonical casing and
+//! SPARQL's `langMatches`.
+
+use std::fmt;
+
+pub const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+
+/// The irregular grandfathered tags, which predate BCP 47's grammar, as the
+/// registry writes them. The regular ones parse like any other tag.
+const IRREGULAR: &[&str] = &[
+    "en-GB-oed",
+    "i-ami",
+    "i-bnn",
+    "i-default",
+    "i-enochian",
+    "i-hak",
+    "i-klingon",
+    "i-lux",
+    "i-mingo",
+    "i-navajo",
+    "i-pwn",
+    "i-tao",
+    "i-tay",
+    "i-tsu",
+    "sgn-BE-FR",
+    "sgn-BE-NL",
+    "sgn-CH-DE",
+];
+
+/// A well-formed BCP 47 language tag, kept in its canonical casing: scripts
+/// in title case (`Latn`), regions in upper case (`DE`) and everything else
+/// in lower case, so tags that differ only in case are equal.
+///
+/// Whether the subtags are registered is not checked, only their shape.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LanguageTag {
+    tag: String,
+}
+
+fn is_alpha(subtag: &str) -> bool {
+    subtag.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_digit(subtag: &str) -> bool {
+    subtag.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn title_case(subtag: &str) -> String {
+    subtag[..1].to_ascii_uppercase() + &subtag[1..].to_ascii_lowercase()
+}
+
+impl LanguageTag {
+    /// Reads a tag in any casing, following RFC 5646's `Language-Tag`.
+    pub fn parse(tag: &str) -> Option<LanguageTag> {
+        if let Some(irregular) = IRREGULAR.iter().find(|irregular| irregular.eq_ignore_ascii_case(tag)) {
+            return Some(LanguageTag { tag: (*irregular).to_owned() });
+        }
+
+        let subtags: Vec<&str> = tag.split('-').collect();
+        if subtags.iter().any(|subtag| subtag.is_empty() || subtag.len() > 8 || !subtag.bytes().all(|b| b.is_ascii_alphanumeric())) {
+            return None;
+        }
+        let next = |i: usize, shape: &dyn Fn(&str) -> bool| subtags.get(i).filter(|subtag| shape(subtag)).is_some();
+        let mut canonical: Vec<String> = Vec::with_capacity(subtags.len());
+        let mut i = 0;
+
+        if !subtags[0].eq_ignore_ascii_case("x") {
+            // language, with up to three extended language subtags
+            if !next(0, &|subtag| subtag.len() >= 2 && is_alpha(subtag)) {
+                return None;
+            }
+            canonical.push(subtags[0].to_ascii_lowercase());
+            i += 1;
+            if subtags[0].len() <= 3 {
+                while i < 4 && next(i, &|subtag| subtag.len() == 3 && is_alpha(subtag)) {
+                    canonical.push(subtags[i].to_ascii_lowercase());
+                    i += 1;
+                }
+            }
+
+            if next(i, &|subtag| subtag.len() == 4 && is_alpha(subtag)) {
+                canonical.push(title_case(subtags[i]));
+                i += 1;
+            }
+            if next(i, &|subtag| (subtag.len() == 2 && is_alpha(subtag)) || (subtag.len() == 3 && is_digit(subtag))) {
+                canonical.push(subtags[i].to_ascii_uppercase());
+                i += 1;
+            }
+            while next(i, &|subtag| subtag.len() >= 5 || (subtag.len() == 4 && subtag.as_bytes()[0].is_ascii_digit())) {
+                canonical.push(subtags[i].to_ascii_lowercase());
+                i += 1;
+            }
+
+            // extensions: a singleton other than `x` and at least one subtag
+            while next(i, &|subtag| subtag.len() == 1 && !subtag.eq_ignore_ascii_case("x")) {
+                canonical.push(subtags[i].to_ascii_lowercase());
+                i += 1;
+                let start = i;
+                while next(i, &|subtag| subtag.len() >= 2) {
+                    canonical.push(subtags[i].to_ascii_lowercase());
+                    i += 1;
+                }
+                if i == start {
+                    return None;
+                }
+            }
+        }
+
+        // private use: `x` and at least one subtag of any length
+        if next(i, &|subtag| subtag.eq_ignore_ascii_case("x")) {
+            canonical.extend(subtags[i..].iter().map(|subtag| subtag.to_ascii_lowercase()));
+            i = subtags.len().max(i + 2);
+        }
+
+        Some(LanguageTag { tag: canonical.join("-") }).filter(|_| i == subtags.len())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.tag
+    }
+
+    /// The first subtag: the language, or `x` or `i` for private use and
+    /// grandfathered tags.
+    pub fn primary_language(&self) -> &str {
+        self.tag.split('-').next().unwrap_or_default()
+    }
+
+    /// Whether the tag falls within the language range `range`; see
+    /// [`lang_matches`].
+    pub fn matches(&self, range: &str) -> bool {
+        lang_matches(&self.tag, range)
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.tag)
+    }
+}
+
+impl AsRef<str> for LanguageTag {
+    fn as_ref(&self) -> &str {
+        &self.tag
+    }
+}
+
+/// SPARQL's `langMatches(tag, range)`, RFC 4647's basic filtering: `*`
+/// matches any tag but the empty one (of a literal without a tag), and any
+/// other range matches tags that equal it or start with it followed by `-`,
+/// ignoring case. So `de` matches `de` and `DE-at` but not `deu`.
+pub fn lang_matches(tag: &str, range: &str) -> bool {
+    if range == "*" {
+        return !tag.is_empty();
+    }
+
+    let (tag, range) = (tag.as_bytes(), range.as_bytes());
+    tag.len() >= range.len() && tag[..range.len()].eq_ignore_ascii_case(range) && (tag.len() == range.len() || tag[range.len()] == b'-')
+}
+
+/// A string literal and its language tag, if it has one, so functions like
+/// `to_upper` can keep the tag of `"straße"@de` in their result.
+///
+/// As a parameter it takes any literal, with its tag if it is an
+/// `rdf:langString`. IRIs, blank nodes and quoted triples are type errors, and
+/// so is a tag that is not well-formed. As a result it is a language-tagged
+/// literal if it has a tag and a simple literal if not.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LangString {
+    value: String,
+    lang: Option<LanguageTag>,
+}
+
+impl LangString {
+    pub fn new<S: Into<String>>(value: S, lang: LanguageTag) -> LangString {
+        LangString { value: value.into(), lang: Some(lang) }
+    }
+
+    /// A string without a tag.
+    pub fn untagged<S: Into<String>>(value: S) -> LangString {
+        LangString { value: value.into(), lang: None }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn lang(&self) -> Option<&LanguageTag> {
+        self.lang.as_ref()
+    }
+
+    /// Replaces the string, keeping the tag.
+    pub fn map<F: FnOnce(String) -> String>(self, f: F) -> LangString {
+        LangString { value: f(self.value), lang: self.lang }
+    }
+
+    pub fn into_value(self) -> String {
+        self.value
+    }
+}
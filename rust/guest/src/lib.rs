@@ -0,0 +1,410 @@
+//! Guest side of the `wasm:call` calling convention.
+//!
+//! The host writes a SPARQL JSON select result into memory obtained from
+//! `allocate` and calls `evaluate(input, input_len, output_len)`, which
+//! returns a pointer to the SPARQL JSON result and writes its length to the
+//! `u32` at `output_len`. The host then releases the input with
+//! `deallocate` and the result with `free_result`. Modules built with the
+//! `nul-terminated` feature also export the older `internalEvaluate`, which
+//! takes and returns NUL-terminated strings. The [`abi`] module tells hosts
+//! which of these a given module speaks. This crate owns all of that so a
+//! function is just a plain Rust function:
+//!
+//! ```ignore
+//! use stardog_wasm_guest::{export_functions, stardog_function};
+//!
+//! #[stardog_function]
+//! fn to_upper(value: &str) -> String {
+//!     value.to_uppercase()
+//! }
+//!
+//! #[stardog_function]
+//! fn to_lower(value: &str) -> String {
+//!     value.to_lowercase()
+//! }
+//!
+//! export_functions!(to_upper, to_lower);
+//! ```
+//!
+//! which is called as `wasm:call(<strings.wasm>, "to_upper", "stardog")`.
+//!
+//! Parameters are converted with [`FromArgument`] and the return value with
+//! [`IntoResults`], which also covers tuples, `Vec`s and [`Rows`] for functions
+//! returning more than one value, and `Option`s for values left unbound.
+//! Structs get the same through `#[derive(FromArgument, IntoResult, IntoRow)]`,
+//! and types with no RDF mapping are compile errors. An `#[accept(...)]` attribute on a parameter
+//! first checks its argument against an [`Accept`] rule. [`export_function!`] is the lower level alternative for a
+//! module with a single function that wants to look at [`Args`] itself; it
+//! takes no function name.
+//!
+//! Modules also export `evaluate_batch(input, input_len, output_len)`, which
+//! takes one row per call with every `value[i]` of that call bound in the row,
+//! and returns a JSON array with one result document per row, in order. Each
+//! of them is either the row's results or its error envelope, so one failing
+//! row does not fail the others. The function runs once per row; nothing
+//! changes on the guest side.
+//!
+//! `evaluate_binary` and `evaluate_batch_binary` are the same two entry points
+//! for hosts that would rather skip JSON, taking and returning the [`binary`]
+//! encoding. Modules advertise them with [`abi::features::BINARY`].
+//!
+//! Numbers and dates convert to and from their XSD datatypes as [`Numeric`]
+//! and the types of the [`DateTime`] family, and [`LangString`] keeps the
+//! language tag of a string.
+//!
+//! Functions that need configuration loaded once per instance rather than
+//! once per call keep it in an [`Init`] state, which the host sets up (and
+//! may later replace) through the `init(config, config_len)` export.
+//!
+//! Custom SPARQL aggregates implement [`Aggregate`] and are exported through
+//! `agg_init`, `agg_step`, `agg_merge` and `agg_finalize`, and table functions
+//! yielding any number of rows implement [`TableFunction`] and are paged
+//! through with `cursor_open`, `cursor_next` and `cursor_close`.
+//!
+//! A panic inside a function still traps, but its message and location are
+//! kept for the host to read through the `last_error`/`last_error_len`
+//! exports.
+//!
+//! Modules built with the `host-log` feature keep what functions log with the
+//! `log` crate for the host, which turns logging on with `set_log_level` and
+//! takes the records through `next_log_record`.
+
+use std::mem;
+use std::os::raw::{c_char, c_void};
+
+mod accept;
+pub mod abi;
+mod aggregate;
+mod arena;
+mod args;
+pub mod binary;
+mod convert;
+mod datetime;
+mod describe;
+mod error;
+mod lang;
+mod logger;
+mod numeric;
+mod output;
+mod panic;
+mod registry;
+mod results;
+mod state;
+mod table;
+mod term;
+
+pub use accept::Accept;
+pub use aggregate::{agg_finalize, agg_free, agg_init, agg_merge, agg_step, new_state, Aggregate, AggregateEntry, Handle};
+pub use args::Args;
+pub use convert::{FromArgument, IntoResult, ANY_TERM, NUMERIC, TRIPLE, XSD};
+pub use datetime::{days_in_month, Date, DateTime, Duration, GYear, Time, Timezone};
+pub use describe::{AggregateDescription, FunctionDescription, ModuleDescription, Parameter, TableDescription};
+pub use error::{Error, ErrorCode};
+pub use lang::{lang_matches, LangString, LanguageTag, RDF_LANG_STRING};
+pub use numeric::{canonical_double, canonical_float, parse_double, parse_float, Decimal, Integer, Numeric, DIVISION_SCALE};
+pub use output::{IntoResults, IntoRow, IntoValue, Rows, ARRAY};
+pub use panic::last_error_message;
+pub use registry::{dispatch, Entry, Function};
+pub use results::{Binding, Bindings, Head, SelectResults};
+pub use state::{init, state, Init};
+pub use table::{cursor_close, cursor_next, cursor_open, open_cursor, TableEntry, TableFunction};
+pub use term::{Term, Triple};
+pub use stardog_wasm_guest_macros::{stardog_function, FromArgument, IntoResult, IntoRow};
+
+fn result_document(result: Result<SelectResults, Error>) -> String {
+    let results = result.unwrap_or_else(SelectResults::error);
+
+    serde_json::to_string(&results).expect("results always serialize")
+}
+
+fn decode_input<'a, T>(input: Result<&'a str, std::str::Utf8Error>, parse: fn(&'a str) -> serde_json::Result<T>) -> Result<T, Error> {
+    let input = input.map_err(|e| Error::new(ErrorCode::InvalidInput, format!("input is not UTF-8: {}", e)))?;
+
+    parse(input).map_err(|e| Error::new(ErrorCode::InvalidInput, format!("input is not SPARQL JSON: {}", e)))
+}
+
+#[no_mangle]
+pub extern "C" fn allocate(size: usize) -> *mut c_void {
+    let mut buffer = Vec::<u8>::with_capacity(size);
+    let pointer = buffer.as_mut_ptr();
+    mem::forget(buffer);
+
+    pointer as *mut c_void
+}
+
+/// # Safety
+///
+/// `pointer` must have been returned by `allocate(capacity)` and not freed since.
+#[no_mangle]
+pub unsafe extern "C" fn deallocate(pointer: *mut c_void, capacity: usize) {
+    let _ = Vec::from_raw_parts(pointer as *mut u8, 0, capacity);
+}
+
+/// Runs `function` over the `input_len` bytes of arguments at `input`, stores
+/// the length of the encoded result in `output_len` and returns a pointer to it.
+///
+/// # Safety
+///
+/// `input` must point to `input_len` readable bytes and `output_len` to a
+/// writable `usize` (four bytes on wasm32, no alignment needed). Called by
+/// the code [`export_function!`] generates; not meant to be used directly.
+#[doc(hidden)]
+pub unsafe fn evaluate<F, R>(input: *const u8, input_len: usize, output_len: *mut usize, function: F) -> *mut u8
+where
+    F: FnOnce(&Args) -> R,
+    R: IntoResults,
+{
+    let _call = begin_call();
+
+    let input = std::str::from_utf8(std::slice::from_raw_parts(input, input_len));
+    into_sized_result_buffer(evaluate_str(input, function), output_len)
+}
+
+/// Runs `function` once for every row of the batch at `input` and returns a
+/// JSON array of the result documents, like [`evaluate`] does for one call.
+///
+/// If the batch itself cannot be decoded the result is a single error
+/// document rather than an array.
+///
+/// # Safety
+///
+/// As for [`evaluate`].
+#[doc(hidden)]
+pub unsafe fn evaluate_batch<F, R>(input: *const u8, input_len: usize, output_len: *mut usize, function: F) -> *mut u8
+where
+    F: Fn(&Args) -> R,
+    R: IntoResults,
+{
+    let _call = begin_call();
+
+    let input = std::str::from_utf8(std::slice::from_raw_parts(input, input_len));
+    let output = match decode_input(input, Args::parse_batch) {
+        Ok(batch) => {
+            let results: Vec<SelectResults> = batch
+                .iter()
+                .map(|args| function(args).into_results().unwrap_or_else(SelectResults::error))
+                .collect();
+            serde_json::to_string(&results).expect("results always serialize")
+        }
+        Err(error) => result_document(Err(error)),
+    };
+
+    into_sized_result_buffer(output, output_len)
+}
+
+/// [`evaluate`] with the arguments and result in the [`binary`] encoding.
+///
+/// # Safety
+///
+/// As for [`evaluate`].
+#[doc(hidden)]
+pub unsafe fn evaluate_binary<F, R>(input: *const u8, input_len: usize, output_len: *mut usize, function: F) -> *mut u8
+where
+    F: FnOnce(&Args) -> R,
+    R: IntoResults,
+{
+    let _call = begin_call();
+
+    let input = std::slice::from_raw_parts(input, input_len);
+    let result = binary::decode_arguments(input).and_then(|args| function(&args).into_results());
+
+    into_sized_result_buffer(binary::encode_results(&result.unwrap_or_else(SelectResults::error)), output_len)
+}
+
+/// [`evaluate_batch`] with the batch and results in the [`binary`] encoding.
+///
+/// # Safety
+///
+/// As for [`evaluate`].
+#[doc(hidden)]
+pub unsafe fn evaluate_batch_binary<F, R>(input: *const u8, input_len: usize, output_len: *mut usize, function: F) -> *mut u8
+where
+    F: Fn(&Args) -> R,
+    R: IntoResults,
+{
+    let _call = begin_call();
+
+    let input = std::slice::from_raw_parts(input, input_len);
+    let output = match binary::decode_batch_arguments(input) {
+        Ok(batch) => {
+            let results: Vec<SelectResults> = batch
+                .iter()
+                .map(|args| function(args).into_results().unwrap_or_else(SelectResults::error))
+                .collect();
+            binary::encode_batch_results(&results)
+        }
+        Err(error) => binary::encode_results(&SelectResults::error(error)),
+    };
+
+    into_sized_result_buffer(output, output_len)
+}
+
+/// Returns `description` as JSON the same way [`evaluate`] returns results.
+///
+/// # Safety
+///
+/// `output_len` must point to a writable `usize`. Called by the code
+/// [`export_functions!`] generates; not meant to be used directly.
+#[doc(hidden)]
+pub unsafe fn describe(output_len: *mut usize, description: ModuleDescription) -> *mut u8 {
+    let output = serde_json::to_string(&description).expect("descriptions always serialize");
+
+    into_sized_result_buffer(output, output_len)
+}
+
+/// The `internalEvaluate` calling convention: a NUL-terminated input and a
+/// NUL-terminated result.
+///
+/// # Safety
+///
+/// `input` must point to a NUL-terminated string.
+#[cfg(feature = "nul-terminated")]
+#[doc(hidden)]
+pub unsafe fn evaluate_nul_terminated<F, R>(input: *const c_char, function: F) -> *mut c_char
+where
+    F: FnOnce(&Args) -> R,
+    R: IntoResults,
+{
+    let _call = begin_call();
+
+    into_result_buffer(evaluate_str(std::ffi::CStr::from_ptr(input).to_str(), function))
+}
+
+/// Sets up for one call into the module. With the `arena` feature everything
+/// allocated until the returned scope is dropped, the result included, comes
+/// from the arena.
+fn begin_call() -> arena::Scope {
+    panic::install_hook();
+    panic::clear_last_error();
+
+    arena::Scope::enter()
+}
+
+fn evaluate_str<F, R>(input: Result<&str, std::str::Utf8Error>, function: F) -> String
+where
+    F: FnOnce(&Args) -> R,
+    R: IntoResults,
+{
+    let result = decode_input(input, Args::parse).and_then(|args| function(&args).into_results());
+
+    result_document(result)
+}
+
+/// Hands `output` to the host as a NUL-terminated string, to be released
+/// with `free_result`.
+pub(crate) fn into_result_buffer<B: Into<Vec<u8>>>(output: B) -> *mut c_char {
+    let mut bytes = output.into();
+    bytes.push(0);
+
+    Box::into_raw(bytes.into_boxed_slice()) as *mut c_char
+}
+
+unsafe fn into_sized_result_buffer<B: Into<Vec<u8>>>(output: B, output_len: *mut usize) -> *mut u8 {
+    let output = output.into();
+    output_len.write_unaligned(output.len());
+
+    into_result_buffer(output) as *mut u8
+}
+
+/// Releases a result returned by any of the `evaluate` exports,
+/// `internalEvaluate`, `describe`, `agg_finalize`, `cursor_next` or
+/// `next_log_record`.
+///
+/// Results belong to the host once returned; it must call this exactly once
+/// per result after reading it, with `len` the length of the result (not
+/// counting the NUL that still follows every result, for hosts that scan for
+/// it). Nothing else ever frees them.
+///
+/// # Safety
+///
+/// `pointer` must be a result of this module that has not been freed, and
+/// `len` its length.
+#[no_mangle]
+pub unsafe extern "C" fn free_result(pointer: *mut c_char, len: usize) {
+    drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(pointer as *mut u8, len + 1)));
+}
+
+/// Exports `$function: fn(&Args) -> impl IntoResults` as the module's
+/// `evaluate(input, input_len, output_len) -> result` and
+/// `evaluate_batch(input, input_len, output_len) -> results`, their
+/// `evaluate_binary` and `evaluate_batch_binary` counterparts, and also as
+/// `internalEvaluate` with the `nul-terminated` feature.
+#[macro_export]
+macro_rules! export_function {
+    ($function:path) => {
+        $crate::__export_module!($function, 0);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_module {
+    ($function:path, $features:expr) => {
+        #[export_name = "stardog_wasm_abi_version"]
+        pub extern "C" fn __stardog_wasm_abi_version() -> u32 {
+            $crate::abi::ABI_VERSION
+        }
+
+        #[export_name = "stardog_wasm_abi_features"]
+        pub extern "C" fn __stardog_wasm_abi_features() -> u32 {
+            $crate::abi::BASE_FEATURES | $features
+        }
+
+        /// # Safety
+        ///
+        /// Called by the host with buffers obtained from `allocate`.
+        #[export_name = "evaluate"]
+        pub unsafe extern "C" fn __stardog_wasm_evaluate(input: *const u8, input_len: usize, output_len: *mut usize) -> *mut u8 {
+            $crate::evaluate(input, input_len, output_len, $function)
+        }
+
+        /// # Safety
+        ///
+        /// Called by the host with buffers obtained from `allocate`.
+        #[export_name = "evaluate_batch"]
+        pub unsafe extern "C" fn __stardog_wasm_evaluate_batch(input: *const u8, input_len: usize, output_len: *mut usize) -> *mut u8 {
+            $crate::evaluate_batch(input, input_len, output_len, $function)
+        }
+
+        /// # Safety
+        ///
+        /// Called by the host with buffers obtained from `allocate`.
+        #[export_name = "evaluate_binary"]
+        pub unsafe extern "C" fn __stardog_wasm_evaluate_binary(input: *const u8, input_len: usize, output_len: *mut usize) -> *mut u8 {
+            $crate::evaluate_binary(input, input_len, output_len, $function)
+        }
+
+        /// # Safety
+        ///
+        /// Called by the host with buffers obtained from `allocate`.
+        #[export_name = "evaluate_batch_binary"]
+        pub unsafe extern "C" fn __stardog_wasm_evaluate_batch_binary(input: *const u8, input_len: usize, output_len: *mut usize) -> *mut u8 {
+            $crate::evaluate_batch_binary(input, input_len, output_len, $function)
+        }
+
+        $crate::__export_nul_terminated!($function);
+    };
+}
+
+#[cfg(feature = "nul-terminated")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_nul_terminated {
+    ($function:path) => {
+        /// # Safety
+        ///
+        /// Called by the host with a NUL-terminated buffer obtained from `allocate`.
+        #[no_mangle]
+        #[allow(non_snake_case)]
+        pub unsafe extern "C" fn internalEvaluate(input: *mut ::std::os::raw::c_char) -> *mut ::std::os::raw::c_char {
+            $crate::evaluate_nul_terminated(input, $function)
+        }
+    };
+}
+
+#[cfg(not(feature = "nul-terminated"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_nul_terminated {
+    ($function:path) => {};
+}
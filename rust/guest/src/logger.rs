@@ -0,0 +1,121 @@
+//! The `host-log` feature: a `log` backend that keeps every record for the
+//! host to take through the `next_log_record(output_len)` export, so
+//! `log::debug!` in a function ends up in the host's log.
+//!
+//! Nothing is logged until the host turns logging on with `set_log_level`,
+//! so for a host that knows neither export the `log` macros do nothing. A
+//! host that turns it on takes the records after each call; only the newest
+//! 1024 are kept in the meantime, and the host is told how many were dropped.
+//!
+//! Levels are those of `log::Level`: 1 error, 2 warn, 3 info, 4 debug and
+//! 5 trace. Without the feature no logger is installed at all.
+
+#[cfg(feature = "host-log")]
+pub(crate) use self::enabled::*;
+
+#[cfg(not(feature = "host-log"))]
+pub(crate) use self::disabled::*;
+
+#[cfg(feature = "host-log")]
+mod enabled {
+    use std::collections::VecDeque;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+
+    use crate::arena;
+
+    /// How many records are kept for a host that does not take them.
+    const MAX_RECORDS: usize = 1024;
+
+    static RECORDS: Mutex<VecDeque<(Level, String)>> = Mutex::new(VecDeque::new());
+
+    /// How many records were dropped since the host last took one.
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    struct HostLogger;
+
+    impl Log for HostLogger {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.level() <= log::max_level()
+        }
+
+        fn log(&self, record: &Record) {
+            if self.enabled(record.metadata()) {
+                // the record has to survive the arena being reset
+                arena::outside(|| {
+                    let message = format!("{}: {}", record.target(), record.args());
+                    if let Ok(mut records) = RECORDS.lock() {
+                        if records.len() == MAX_RECORDS {
+                            records.pop_front();
+                            DROPPED.fetch_add(1, Ordering::Relaxed);
+                        }
+                        records.push_back((record.level(), message));
+                    }
+                })
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: HostLogger = HostLogger;
+
+    /// Makes the buffer the logger. What gets through is up to
+    /// `set_log_level`, which lets nothing through until the host calls it.
+    pub(crate) fn install() {
+        let _ = log::set_logger(&LOGGER);
+    }
+
+    /// Only keeps records up to `level` (0 for none, 1 for errors only, ...,
+    /// 5 for everything), so the module does not even format the others.
+    #[no_mangle]
+    pub extern "C" fn set_log_level(level: u32) {
+        install();
+
+        let filter = match level {
+            0 => LevelFilter::Off,
+            1 => LevelFilter::Error,
+            2 => LevelFilter::Warn,
+            3 => LevelFilter::Info,
+            4 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        };
+        log::set_max_level(filter);
+    }
+
+    /// Takes the oldest record that the host has not taken yet: its level as
+    /// one byte followed by the UTF-8 message, prefixed with the record's
+    /// target. If records were dropped since the last one was taken, a warning
+    /// saying how many comes first. Returns null once there are none left.
+    /// Released with `free_result`, like any result.
+    ///
+    /// # Safety
+    ///
+    /// `output_len` must point to a writable `usize`.
+    #[no_mangle]
+    pub unsafe extern "C" fn next_log_record(output_len: *mut usize) -> *mut u8 {
+        let record = match DROPPED.swap(0, Ordering::Relaxed) {
+            0 => RECORDS.lock().ok().and_then(|mut records| records.pop_front()),
+            dropped => Some((Level::Warn, format!("stardog_wasm_guest: dropped {} log records the host did not take in time", dropped))),
+        };
+
+        match record {
+            Some((level, message)) => {
+                let mut output = Vec::with_capacity(message.len() + 1);
+                output.push(level as u8);
+                output.extend_from_slice(message.as_bytes());
+                crate::into_sized_result_buffer(output, output_len)
+            }
+            None => std::ptr::null_mut(),
+        }
+    }
+
+    // the levels are the ones `next_log_record` documents
+    const _: () = assert!(Level::Error as u32 == 1 && Level::Trace as u32 == 5);
+}
+
+#[cfg(not(feature = "host-log"))]
+mod disabled {
+    pub(crate) fn install() {}
+}
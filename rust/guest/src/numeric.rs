@@ -0,0 +1,797 @@
+//! The XSD numeric tower: `xsd:integer` of any size, `xsd:decimal`,
+//! `xsd:float` and `xsd:double`, with the lexical forms, canonical forms and
+//! type promotion of SPARQL 1.1 (XPath's `op:numeric-*`).
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::{Error, ErrorCode, Term, XSD};
+
+/// The datatypes derived from `xsd:integer`, with their bounds.
+const INTEGERS: &[(&str, Option<i128>, Option<i128>)] = &[
+    ("integer", None, None),
+    ("nonPositiveInteger", None, Some(0)),
+    ("negativeInteger", None, Some(-1)),
+    ("long", Some(i64::MIN as i128), Some(i64::MAX as i128)),
+    ("int", Some(i32::MIN as i128), Some(i32::MAX as i128)),
+    ("short", Some(i16::MIN as i128), Some(i16::MAX as i128)),
+    ("byte", Some(i8::MIN as i128), Some(i8::MAX as i128)),
+    ("nonNegativeInteger", Some(0), None),
+    ("unsignedLong", Some(0), Some(u64::MAX as i128)),
+    ("unsignedInt", Some(0), Some(u32::MAX as i128)),
+    ("unsignedShort", Some(0), Some(u16::MAX as i128)),
+    ("unsignedByte", Some(0), Some(u8::MAX as i128)),
+    ("positiveInteger", Some(1), None),
+];
+
+/// Fractional digits kept by decimal division, which is truncated there.
+pub const DIVISION_SCALE: u32 = 24;
+
+/// Where `datatype` is in the promotion order: integers (of any derived
+/// type), then decimals, floats and doubles, each promoting to the ones after
+/// it. `None` for non-numeric datatypes.
+pub(crate) fn rank(datatype: &str) -> Option<u8> {
+    match datatype.strip_prefix(XSD)? {
+        "decimal" => Some(1),
+        "float" => Some(2),
+        "double" => Some(3),
+        local if INTEGERS.iter().any(|(name, _, _)| *name == local) => Some(0),
+        _ => None,
+    }
+}
+
+// Magnitudes are little-endian base 2^32 digits without trailing zeros.
+
+fn trim(mut digits: Vec<u32>) -> Vec<u32> {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    digits
+}
+
+fn compare_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, &digit) in long.iter().enumerate() {
+        let total = digit as u64 + short.get(i).copied().unwrap_or(0) as u64 + carry;
+        sum.push(total as u32);
+        carry = total >> 32;
+    }
+    sum.push(carry as u32);
+
+    trim(sum)
+}
+
+/// `a - b`, where `a` is at least `b`.
+fn sub_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &digit) in a.iter().enumerate() {
+        let mut total = digit as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        borrow = if total < 0 { 1 } else { 0 };
+        if total < 0 {
+            total += 1 << 32;
+        }
+        difference.push(total as u32);
+    }
+
+    trim(difference)
+}
+
+fn mul_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut product = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let total = product[i + j] as u64 + x as u64 * y as u64 + carry;
+            product[i + j] = total as u32;
+            carry = total >> 32;
+        }
+        product[i + b.len()] = carry as u32;
+    }
+
+    trim(product)
+}
+
+/// `digits * factor + addend`, in place.
+fn mul_small(digits: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for digit in digits.iter_mut() {
+        let total = *digit as u64 * factor as u64 + carry;
+        *digit = total as u32;
+        carry = total >> 32;
+    }
+    if carry != 0 {
+        digits.push(carry as u32);
+    }
+}
+
+/// `digits / divisor`, in place, returning the remainder.
+fn div_small(digits: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut remainder = 0u64;
+    for digit in digits.iter_mut().rev() {
+        let total = (remainder << 32) | *digit as u64;
+        *digit = (total / divisor as u64) as u32;
+        remainder = total % divisor as u64;
+    }
+    *digits = trim(std::mem::take(digits));
+
+    remainder as u32
+}
+
+/// Truncating `a / b` and its remainder, for a non-zero `b`.
+fn div_rem_magnitudes(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if let [divisor] = b {
+        let mut quotient = a.to_vec();
+        let remainder = div_small(&mut quotient, *divisor);
+        return (quotient, trim(vec![remainder]));
+    }
+
+    // shift-and-subtract, a bit at a time
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+    for bit in (0..a.len() * 32).rev() {
+        mul_small(&mut remainder, 2, (a[bit / 32] >> (bit % 32)) & 1);
+        if compare_magnitudes(&remainder, b) != Ordering::Less {
+            remainder = sub_magnitudes(&remainder, b);
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+
+    (trim(quotient), remainder)
+}
+
+/// Strips XSD's leading and trailing whitespace and an optional sign, which
+/// is returned as whether the number is negative.
+fn split_sign(lexical: &str) -> (bool, &str) {
+    let lexical = lexical.trim_matches(|c| matches!(c, ' ' | '\t' | '\n' | '\r'));
+    match lexical.as_bytes().first() {
+        Some(b'-') => (true, &lexical[1..]),
+        Some(b'+') => (false, &lexical[1..]),
+        _ => (false, lexical),
+    }
+}
+
+/// An `xsd:integer` of any size.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Integer {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+impl Integer {
+    fn new(negative: bool, magnitude: Vec<u32>) -> Integer {
+        let magnitude = trim(magnitude);
+        Integer { negative: negative && !magnitude.is_empty(), magnitude }
+    }
+
+    /// Reads the lexical form of an `xsd:integer`, `[+-]?[0-9]+`.
+    pub fn parse(lexical: &str) -> Option<Integer> {
+        let (negative, digits) = split_sign(lexical);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        Some(Integer::new(negative, Integer::magnitude_of(digits)))
+    }
+
+    /// The magnitude of a non-empty string of ASCII digits.
+    fn magnitude_of(digits: &str) -> Vec<u32> {
+        let mut magnitude = Vec::new();
+        let mut rest = digits;
+        while !rest.is_empty() {
+            // nine digits at a time, the odd ones first
+            let (chunk, tail) = rest.split_at(match rest.len() % 9 { 0 => 9, odd => odd });
+            mul_small(&mut magnitude, 10u32.pow(chunk.len() as u32), chunk.parse().expect("chunks are digits"));
+            rest = tail;
+        }
+
+        trim(magnitude)
+    }
+
+    fn with_sign(self, negative: bool) -> Integer {
+        Integer::new(negative, self.magnitude)
+    }
+
+    pub fn zero() -> Integer {
+        Integer::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> Integer {
+        Integer::new(false, self.magnitude.clone())
+    }
+
+    /// `self * 10^exponent`.
+    fn scale_up(&self, exponent: u32) -> Integer {
+        let mut magnitude = self.magnitude.clone();
+        for _ in 0..exponent / 9 {
+            mul_small(&mut magnitude, 1_000_000_000, 0);
+        }
+        mul_small(&mut magnitude, 10u32.pow(exponent % 9), 0);
+
+        Integer::new(self.negative, magnitude)
+    }
+
+    /// The truncated quotient and the remainder, which has the sign of
+    /// `self`, or `None` if `divisor` is zero.
+    pub fn div_rem(&self, divisor: &Integer) -> Option<(Integer, Integer)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        let (quotient, remainder) = div_rem_magnitudes(&self.magnitude, &divisor.magnitude);
+
+        Some((Integer::new(self.negative != divisor.negative, quotient), Integer::new(self.negative, remainder)))
+    }
+
+    /// The nearest double.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().expect("integers are valid doubles")
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        if self.magnitude.len() > 4 {
+            return None;
+        }
+        let magnitude = self.magnitude.iter().rev().fold(0u128, |value, &digit| (value << 32) | digit as u128);
+
+        if self.negative {
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+}
+
+impl From<i128> for Integer {
+    fn from(value: i128) -> Integer {
+        Integer::from(value.unsigned_abs()).with_sign(value < 0)
+    }
+}
+
+impl From<u128> for Integer {
+    fn from(value: u128) -> Integer {
+        Integer::new(false, (0..4).map(|i| (value >> (32 * i)) as u32).collect())
+    }
+}
+
+macro_rules! integer_conversions {
+    ($($t:ty => $wide:ty),*) => {
+        $(
+            impl From<$t> for Integer {
+                fn from(value: $t) -> Integer {
+                    Integer::from(value as $wide)
+                }
+            }
+
+            impl TryFrom<&Integer> for $t {
+                type Error = Error;
+
+                fn try_from(value: &Integer) -> Result<$t, Error> {
+                    value.to_i128().and_then(|value| <$t>::try_from(value).ok()).ok_or_else(|| {
+                        Error::new(ErrorCode::TypeError, format!("{} does not fit in {}", value, stringify!($t)))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+integer_conversions!(i8 => i128, i16 => i128, i32 => i128, i64 => i128, isize => i128, u8 => u128, u16 => u128, u32 => u128, u64 => u128, usize => u128);
+
+impl TryFrom<&Integer> for i128 {
+    type Error = Error;
+
+    fn try_from(value: &Integer) -> Result<i128, Error> {
+        value.to_i128().ok_or_else(|| Error::new(ErrorCode::TypeError, format!("{} does not fit in i128", value)))
+    }
+}
+
+impl Ord for Integer {
+    fn cmp(&self, other: &Integer) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.magnitude, &other.magnitude),
+            (true, true) => compare_magnitudes(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for Integer {
+    fn partial_cmp(&self, other: &Integer) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for Integer {
+    type Output = Integer;
+
+    fn neg(self) -> Integer {
+        let negative = !self.negative;
+        self.with_sign(negative)
+    }
+}
+
+impl Add for Integer {
+    type Output = Integer;
+
+    fn add(self, other: Integer) -> Integer {
+        if self.negative == other.negative {
+            return Integer::new(self.negative, add_magnitudes(&self.magnitude, &other.magnitude));
+        }
+
+        match compare_magnitudes(&self.magnitude, &other.magnitude) {
+            Ordering::Less => Integer::new(other.negative, sub_magnitudes(&other.magnitude, &self.magnitude)),
+            _ => Integer::new(self.negative, sub_magnitudes(&self.magnitude, &other.magnitude)),
+        }
+    }
+}
+
+impl Sub for Integer {
+    type Output = Integer;
+
+    fn sub(self, other: Integer) -> Integer {
+        self + -other
+    }
+}
+
+impl Mul for Integer {
+    type Output = Integer;
+
+    fn mul(self, other: Integer) -> Integer {
+        Integer::new(self.negative != other.negative, mul_magnitudes(&self.magnitude, &other.magnitude))
+    }
+}
+
+/// The canonical form: no sign unless negative and no leading zeros.
+impl fmt::Display for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut magnitude = self.magnitude.clone();
+        let mut chunks = Vec::new();
+        while !magnitude.is_empty() {
+            chunks.push(div_small(&mut magnitude, 1_000_000_000));
+        }
+
+        if self.negative {
+            f.write_str("-")?;
+        }
+        match chunks.split_last() {
+            None => f.write_str("0"),
+            Some((first, rest)) => {
+                write!(f, "{}", first)?;
+                rest.iter().rev().try_for_each(|chunk| write!(f, "{:09}", chunk))
+            }
+        }
+    }
+}
+
+/// An `xsd:decimal`: an integer divided by a power of ten.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    /// Never a multiple of ten unless `scale` is zero, so that equal
+    /// decimals are equal values.
+    unscaled: Integer,
+    scale: u32,
+}
+
+impl Decimal {
+    fn new(unscaled: Integer, scale: u32) -> Decimal {
+        let (mut unscaled, mut scale) = (unscaled, scale);
+        while scale > 0 {
+            let mut magnitude = unscaled.magnitude.clone();
+            if div_small(&mut magnitude, 10) != 0 {
+                break;
+            }
+            unscaled = Integer::new(unscaled.negative, magnitude);
+            scale -= 1;
+        }
+
+        Decimal { unscaled, scale }
+    }
+
+    /// Reads the lexical form of an `xsd:decimal`, `[+-]?[0-9]*(\.[0-9]*)?`
+    /// with at least one digit.
+    pub fn parse(lexical: &str) -> Option<Decimal> {
+        let (negative, number) = split_sign(lexical);
+        let (whole, fraction) = match number.find('.') {
+            Some(point) => (&number[..point], &number[point + 1..]),
+            None => (number, ""),
+        };
+        let digits = format!("{}{}", whole, fraction);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        Some(Decimal::new(Integer::new(negative, Integer::magnitude_of(&digits)), fraction.len() as u32))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.unscaled.is_zero()
+    }
+
+    /// Both as integers with the same scale, and that scale.
+    fn aligned(&self, other: &Decimal) -> (Integer, Integer, u32) {
+        let scale = self.scale.max(other.scale);
+        (self.unscaled.scale_up(scale - self.scale), other.unscaled.scale_up(scale - other.scale), scale)
+    }
+
+    /// `self / divisor`, truncated after [`DIVISION_SCALE`] fractional digits,
+    /// or `None` if `divisor` is zero.
+    pub fn checked_div(&self, divisor: &Decimal) -> Option<Decimal> {
+        // (a / 10^s) / (b / 10^t) = (a * 10^(t + n) / b) / 10^(s + n)
+        let dividend = self.unscaled.scale_up(divisor.scale + DIVISION_SCALE);
+        let (quotient, _) = dividend.div_rem(&divisor.unscaled)?;
+
+        Some(Decimal::new(quotient, self.scale + DIVISION_SCALE))
+    }
+
+    /// The nearest double.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().expect("decimals are valid doubles")
+    }
+}
+
+impl From<Integer> for Decimal {
+    fn from(value: Integer) -> Decimal {
+        Decimal::new(value, 0)
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        let (a, b, _) = self.aligned(other);
+        a.cmp(&b)
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        Decimal::new(-self.unscaled, self.scale)
+    }
+}
+
+impl Add for Decimal {
+    type Output = Decimal;
+
+    fn add(self, other: Decimal) -> Decimal {
+        let (a, b, scale) = self.aligned(&other);
+        Decimal::new(a + b, scale)
+    }
+}
+
+impl Sub for Decimal {
+    type Output = Decimal;
+
+    fn sub(self, other: Decimal) -> Decimal {
+        self + -other
+    }
+}
+
+impl Mul for Decimal {
+    type Output = Decimal;
+
+    fn mul(self, other: Decimal) -> Decimal {
+        Decimal::new(self.unscaled * other.unscaled, self.scale + other.scale)
+    }
+}
+
+/// The canonical form: at least one digit on either side of the point and
+/// no other leading or trailing zeros, as in `1.0` or `-0.25`.
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.unscaled.abs().to_string();
+        let scale = self.scale as usize;
+        let digits = if digits.len() <= scale { format!("{}{}", "0".repeat(scale + 1 - digits.len()), digits) } else { digits };
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+
+        if self.unscaled.is_negative() {
+            f.write_str("-")?;
+        }
+        write!(f, "{}.{}", whole, if fraction.is_empty() { "0" } else { fraction })
+    }
+}
+
+/// Whether `lexical` is an `xsd:float` or `xsd:double` lexical form other
+/// than the special values.
+fn is_floating_point(lexical: &str) -> bool {
+    let (mantissa, exponent) = match lexical.find(['e', 'E']) {
+        Some(e) => (&lexical[..e], Some(&lexical[e + 1..])),
+        None => (lexical, None),
+    };
+    let mantissa = mantissa.strip_prefix(['+', '-']).unwrap_or(mantissa);
+    let digits = mantissa.replacen('.', "", 1);
+    let exponent = exponent.map(|exponent| exponent.strip_prefix(['+', '-']).unwrap_or(exponent));
+
+    !digits.is_empty()
+        && digits.bytes().all(|b| b.is_ascii_digit())
+        && exponent.is_none_or(|exponent| !exponent.is_empty() && exponent.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Reads the lexical form of an `xsd:double`, including `INF`, `-INF` and
+/// `NaN`.
+pub fn parse_double(lexical: &str) -> Option<f64> {
+    let lexical = lexical.trim_matches(|c| matches!(c, ' ' | '\t' | '\n' | '\r'));
+    match lexical {
+        "INF" | "+INF" => Some(f64::INFINITY),
+        "-INF" => Some(f64::NEG_INFINITY),
+        "NaN" => Some(f64::NAN),
+        _ if is_floating_point(lexical) => lexical.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Reads the lexical form of an `xsd:float`.
+pub fn parse_float(lexical: &str) -> Option<f32> {
+    let lexical = lexical.trim_matches(|c| matches!(c, ' ' | '\t' | '\n' | '\r'));
+    match lexical {
+        "INF" | "+INF" => Some(f32::INFINITY),
+        "-INF" => Some(f32::NEG_INFINITY),
+        "NaN" => Some(f32::NAN),
+        _ if is_floating_point(lexical) => lexical.parse().ok(),
+        _ => None,
+    }
+}
+
+/// The canonical form of a float or double from Rust's shortest round-trip
+/// scientific notation: `1.5E2`, `1.0E0`, `-0.0E0`, `INF`, `NaN`.
+fn canonical_floating_point(scientific: String, is_nan: bool, is_infinite: bool, is_negative: bool) -> String {
+    if is_nan {
+        return "NaN".to_owned();
+    }
+    if is_infinite {
+        return if is_negative { "-INF" } else { "INF" }.to_owned();
+    }
+
+    match scientific.find('E') {
+        Some(e) if !scientific[..e].contains('.') => format!("{}.0{}", &scientific[..e], &scientific[e..]),
+        _ => scientific,
+    }
+}
+
+/// The canonical form of an `xsd:double`.
+pub fn canonical_double(value: f64) -> String {
+    canonical_floating_point(format!("{:E}", value), value.is_nan(), value.is_infinite(), value < 0.0)
+}
+
+/// The canonical form of an `xsd:float`.
+pub fn canonical_float(value: f32) -> String {
+    canonical_floating_point(format!("{:E}", value), value.is_nan(), value.is_infinite(), value < 0.0)
+}
+
+/// A value of any of SPARQL's numeric datatypes.
+///
+/// Arithmetic and comparisons first promote the operands to the same type,
+/// the later of the two in integer, decimal, float, double, as SPARQL does:
+/// `1 + 2.5` is the decimal `3.5` and `1 / 2` the decimal `0.5`.
+#[derive(Clone, Debug)]
+pub enum Numeric {
+    Integer(Integer),
+    Decimal(Decimal),
+    Float(f32),
+    Double(f64),
+}
+
+impl Numeric {
+    /// The value of a literal of a numeric datatype (derived integer types
+    /// included, within their bounds), or `None` for any other term.
+    pub fn from_term(term: &Term) -> Option<Numeric> {
+        Numeric::parse(term.value()?, term.datatype()?)
+    }
+
+    /// Reads `lexical` as a literal of `datatype`.
+    pub fn parse(lexical: &str, datatype: &str) -> Option<Numeric> {
+        match datatype.strip_prefix(XSD)? {
+            "decimal" => Decimal::parse(lexical).map(Numeric::Decimal),
+            "float" => parse_float(lexical).map(Numeric::Float),
+            "double" => parse_double(lexical).map(Numeric::Double),
+            local => {
+                let (_, min, max) = INTEGERS.iter().find(|(name, _, _)| *name == local)?;
+                let value = Integer::parse(lexical)?;
+                let within = min.is_none_or(|min| value >= Integer::from(min)) && max.is_none_or(|max| value <= Integer::from(max));
+
+                Some(Numeric::Integer(value)).filter(|_| within)
+            }
+        }
+    }
+
+    /// Reads an untyped number the way SPARQL reads one in a query: `1` is an
+    /// integer, `1.5` a decimal and `1.5e0` a double. A query cannot write
+    /// `INF` or `NaN` as a number, so neither is read as one here.
+    pub fn parse_untyped(lexical: &str) -> Option<Numeric> {
+        Integer::parse(lexical)
+            .map(Numeric::Integer)
+            .or_else(|| Decimal::parse(lexical).map(Numeric::Decimal))
+            .or_else(|| parse_double(lexical).filter(|value| value.is_finite()).map(Numeric::Double))
+    }
+
+    /// The datatype of the value, as a full IRI.
+    pub fn datatype(&self) -> &'static str {
+        match self {
+            Numeric::Integer(_) => concat!("http://www.w3.org/2001/XMLSchema#", "integer"),
+            Numeric::Decimal(_) => concat!("http://www.w3.org/2001/XMLSchema#", "decimal"),
+            Numeric::Float(_) => concat!("http://www.w3.org/2001/XMLSchema#", "float"),
+            Numeric::Double(_) => concat!("http://www.w3.org/2001/XMLSchema#", "double"),
+        }
+    }
+
+    pub(crate) fn rank(&self) -> u8 {
+        match self {
+            Numeric::Integer(_) => 0,
+            Numeric::Decimal(_) => 1,
+            Numeric::Float(_) => 2,
+            Numeric::Double(_) => 3,
+        }
+    }
+
+    /// The value promoted to the type at `rank`, which must not be lower than
+    /// its own.
+    pub(crate) fn promote(self, rank: u8) -> Numeric {
+        match (self, rank) {
+            (value, rank) if value.rank() == rank => value,
+            (Numeric::Integer(value), 1) => Numeric::Decimal(Decimal::from(value)),
+            (Numeric::Integer(value), 2) => Numeric::Float(value.to_f64() as f32),
+            (Numeric::Decimal(value), 2) => Numeric::Float(value.to_f64() as f32),
+            (value, _) => Numeric::Double(value.to_f64()),
+        }
+    }
+
+    /// Both values promoted to the same type.
+    fn promoted(self, other: Numeric) -> (Numeric, Numeric) {
+        let rank = self.rank().max(other.rank());
+        (self.promote(rank), other.promote(rank))
+    }
+
+    /// The nearest double.
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Numeric::Integer(value) => value.to_f64(),
+            Numeric::Decimal(value) => value.to_f64(),
+            Numeric::Float(value) => *value as f64,
+            Numeric::Double(value) => *value,
+        }
+    }
+
+    /// `self / divisor`, a decimal if both are integers or decimals, and an
+    /// error if `divisor` is then zero.
+    pub fn checked_div(self, divisor: Numeric) -> Result<Numeric, Error> {
+        match self.promoted(divisor) {
+            (Numeric::Integer(a), Numeric::Integer(b)) => Decimal::from(a).checked_div(&Decimal::from(b)).map(Numeric::Decimal),
+            (Numeric::Decimal(a), Numeric::Decimal(b)) => a.checked_div(&b).map(Numeric::Decimal),
+            (Numeric::Float(a), Numeric::Float(b)) => Some(Numeric::Float(a / b)),
+            (a, b) => Some(Numeric::Double(a.to_f64() / b.to_f64())),
+        }
+        .ok_or_else(|| Error::new(ErrorCode::TypeError, "division by zero"))
+    }
+
+    /// The canonical literal for the value.
+    pub fn into_term(self) -> Term<'static> {
+        Term::typed_literal(self.to_string(), self.datatype())
+    }
+}
+
+/// An argument for a parameter of the type at `rank`: a numeric literal that
+/// promotes to it, or the lexical form of any other literal read as that
+/// type.
+pub(crate) fn argument(term: &Term, rank: u8) -> Option<Numeric> {
+    if term.datatype().and_then(self::rank).is_some() {
+        return Numeric::from_term(term).filter(|value| value.rank() <= rank).map(|value| value.promote(rank));
+    }
+
+    let lexical = term.value()?;
+    match rank {
+        0 => Integer::parse(lexical).map(Numeric::Integer),
+        1 => Decimal::parse(lexical).map(Numeric::Decimal),
+        2 => parse_float(lexical).map(Numeric::Float),
+        _ => parse_double(lexical).map(Numeric::Double),
+    }
+}
+
+macro_rules! numeric_operator {
+    ($trait:ident, $method:ident) => {
+        impl $trait for Numeric {
+            type Output = Numeric;
+
+            fn $method(self, other: Numeric) -> Numeric {
+                match self.promoted(other) {
+                    (Numeric::Integer(a), Numeric::Integer(b)) => Numeric::Integer(a.$method(b)),
+                    (Numeric::Decimal(a), Numeric::Decimal(b)) => Numeric::Decimal(a.$method(b)),
+                    (Numeric::Float(a), Numeric::Float(b)) => Numeric::Float(a.$method(b)),
+                    (a, b) => Numeric::Double(a.to_f64().$method(b.to_f64())),
+                }
+            }
+        }
+    };
+}
+
+numeric_operator!(Add, add);
+numeric_operator!(Sub, sub);
+numeric_operator!(Mul, mul);
+
+impl Neg for Numeric {
+    type Output = Numeric;
+
+    fn neg(self) -> Numeric {
+        match self {
+            Numeric::Integer(value) => Numeric::Integer(-value),
+            Numeric::Decimal(value) => Numeric::Decimal(-value),
+            Numeric::Float(value) => Numeric::Float(-value),
+            Numeric::Double(value) => Numeric::Double(-value),
+        }
+    }
+}
+
+/// Equal after promotion, so `1` equals `1.0` and `1.0e0`.
+impl PartialEq for Numeric {
+    fn eq(&self, other: &Numeric) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Numeric {
+    fn partial_cmp(&self, other: &Numeric) -> Option<Ordering> {
+        match self.clone().promoted(other.clone()) {
+            (Numeric::Integer(a), Numeric::Integer(b)) => Some(a.cmp(&b)),
+            (Numeric::Decimal(a), Numeric::Decimal(b)) => Some(a.cmp(&b)),
+            (a, b) => a.to_f64().partial_cmp(&b.to_f64()),
+        }
+    }
+}
+
+/// The canonical lexical form for the value's datatype.
+impl fmt::Display for Numeric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Numeric::Integer(value) => value.fmt(f),
+            Numeric::Decimal(value) => value.fmt(f),
+            Numeric::Float(value) => f.write_str(&canonical_float(*value)),
+            Numeric::Double(value) => f.write_str(&canonical_double(*value)),
+        }
+    }
+}
+
+impl From<Integer> for Numeric {
+    fn from(value: Integer) -> Numeric {
+        Numeric::Integer(value)
+    }
+}
+
+impl From<Decimal> for Numeric {
+    fn from(value: Decimal) -> Numeric {
+        Numeric::Decimal(value)
+    }
+}
+
+impl From<f32> for Numeric {
+    fn from(value: f32) -> Numeric {
+        Numeric::Float(value)
+    }
+}
+
+impl From<f64> for Numeric {
+    fn from(value: f64) -> Numeric {
+        Numeric::Double(value)
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{Binding, Error, IntoResult, SelectResults, Term};
+
+/// Type reported by `describe` for results the host turns into an array.
+pub const ARRAY: &str = "array";
+
+/// One value of a row: anything [`IntoResult`] converts, or an `Option` of
+/// it, which leaves the variable unbound when it is `None`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no RDF mapping, so it cannot be a result value",
+    note = "results can be terms, strings, booleans, numbers, dates, durations or `LangString`s, or `Option`s of them"
+)]
+pub trait IntoValue {
+    /// The type `describe` reports when this is the whole result.
+    const TYPE: &'static str;
+
+    fn into_value(self) -> Result<Option<Term<'static>>, Error>;
+}
+
+impl<T: IntoResult> IntoValue for T {
+    const TYPE: &'static str = <T as IntoResult>::TYPE;
+
+    fn into_value(self) -> Result<Option<Term<'static>>, Error> {
+        self.into_result().map(Some)
+    }
+}
+
+impl<T: IntoResult> IntoValue for Option<T> {
+    const TYPE: &'static str = <T as IntoResult>::TYPE;
+
+    fn into_value(self) -> Result<Option<Term<'static>>, Error> {
+        self.map(T::into_result).transpose()
+    }
+}
+
+/// One result row: a single value, a tuple of them, or a struct that derives
+/// it to have one value per field.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no RDF mapping, so it cannot be a result row",
+    note = "rows can be single values or tuples of them",
+    note = "a struct of such fields can `#[derive(IntoRow)]`"
+)]
+pub trait IntoRow {
+    /// How many values every row of this type has.
+    const WIDTH: usize;
+    /// The type `describe` reports when this is the whole result.
+    const TYPE: &'static str;
+
+    /// The row's values in order, `None` for those left unbound.
+    fn into_row(self) -> Result<Vec<Option<Term<'static>>>, Error>;
+}
+
+/// Conversion from a function's return value to the rows returned to the host.
+///
+/// `wasm:call` gives back every value of every row in order: a single value
+/// as itself and anything else as an array. So a scalar is one row with one
+/// `result` variable, a tuple is one row with `result[0]`, `result[1]`, ...,
+/// a `Vec` is one row per element, and [`Rows`] is whatever it was built with.
+/// `None` values are left unbound.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no RDF mapping, so it cannot be returned from a function",
+    note = "functions can return terms, strings, booleans, numbers, dates, durations or `LangString`s, tuples or `Option`s of them, `Vec`s of those, or `Rows`",
+    note = "a struct with one such field can `#[derive(IntoResult)]`, and one of several can `#[derive(IntoRow)]`"
+)]
+pub trait IntoResults {
+    /// The type `describe` reports for the result.
+    const TYPE: &'static str;
+
+    fn into_results(self) -> Result<SelectResults, Error>;
+}
+
+impl<T: IntoValue> IntoRow for T {
+    const WIDTH: usize = 1;
+    const TYPE: &'static str = <T as IntoValue>::TYPE;
+
+    fn into_row(self) -> Result<Vec<Option<Term<'static>>>, Error> {
+        Ok(vec![self.into_value()?])
+    }
+}
+
+macro_rules! tuple {
+    ($width:expr => $($t:ident $index:tt),+) => {
+        impl<$($t: IntoValue),+> IntoRow for ($($t,)+) {
+            const WIDTH: usize = $width;
+            const TYPE: &'static str = ARRAY;
+
+            fn into_row(self) -> Result<Vec<Option<Term<'static>>>, Error> {
+                Ok(vec![$(self.$index.into_value()?),+])
+            }
+        }
+    };
+}
+
+tuple!(2 => A 0, B 1);
+tuple!(3 => A 0, B 1, C 2);
+tuple!(4 => A 0, B 1, C 2, D 3);
+tuple!(5 => A 0, B 1, C 2, D 3, E 4);
+tuple!(6 => A 0, B 1, C 2, D 3, E 4, F 5);
+
+/// The variable names for rows of `width` values.
+pub(crate) fn vars(width: usize) -> Vec<String> {
+    if width == 1 {
+        vec!["result".to_owned()]
+    } else {
+        (0..width).map(|i| format!("result[{}]", i)).collect()
+    }
+}
+
+/// Rows with the given variable names, for functions that name their outputs
+/// or decide how many rows to return as they go.
+///
+/// ```ignore
+/// let mut rows = Rows::new(&["token", "position"]);
+/// for (position, token) in text.split_whitespace().enumerate() {
+///     rows.push((token, position))?;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Rows {
+    results: SelectResults,
+}
+
+impl Rows {
+    pub fn new<S: AsRef<str>>(vars: &[S]) -> Rows {
+        Rows { results: SelectResults::new(vars.iter().map(|var| var.as_ref().to_owned())) }
+    }
+
+    /// Adds a row, which must have one value per variable.
+    pub fn push<T: IntoRow>(&mut self, row: T) -> Result<(), Error> {
+        let values = row.into_row()?;
+
+        if values.len() != self.results.vars().len() {
+            return Err(Error::failed(format!(
+                "row has {} values but there are {} variables",
+                values.len(),
+                self.results.vars().len()
+            )));
+        }
+
+        let binding = self
+            .results
+            .vars()
+            .iter()
+            .cloned()
+            .zip(values)
+            .filter_map(|(var, term)| Some((var, term?)))
+            .fold(Binding::new(), |binding, (var, term)| binding.with(var, term));
+        self.results.push(binding);
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.bindings().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.bindings().is_empty()
+    }
+}
+
+impl IntoResults for Rows {
+    const TYPE: &'static str = ARRAY;
+
+    fn into_results(self) -> Result<SelectResults, Error> {
+        Ok(self.results)
+    }
+}
+
+impl IntoResults for SelectResults {
+    const TYPE: &'static str = ARRAY;
+
+    fn into_results(self) -> Result<SelectResults, Error> {
+        Ok(self)
+    }
+}
+
+impl<T: IntoRow> IntoResults for T {
+    const TYPE: &'static str = <T as IntoRow>::TYPE;
+
+    fn into_results(self) -> Result<SelectResults, Error> {
+        let mut rows = Rows { results: SelectResults::new(vars(T::WIDTH)) };
+        rows.push(self)?;
+
+        rows.into_results()
+    }
+}
+
+impl<T: IntoRow> IntoResults for Vec<T> {
+    const TYPE: &'static str = ARRAY;
+
+    fn into_results(self) -> Result<SelectResults, Error> {
+        let mut rows = Rows { results: SelectResults::new(vars(T::WIDTH)) };
+        for row in self {
+            rows.push(row)?;
+        }
+
+        rows.into_results()
+    }
+}
+
+impl<T: IntoResults, E: Into<Error>> IntoResults for Result<T, E> {
+    const TYPE: &'static str = T::TYPE;
+
+    fn into_results(self) -> Result<SelectResults, Error> {
+        self.map_err(Into::into)?.into_results()
+    }
+}
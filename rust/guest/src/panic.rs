@@ -0,0 +1,60 @@
+use std::panic;
+use std::sync::{Mutex, Once};
+
+use crate::{arena, logger};
+
+static INSTALL: Once = Once::new();
+static LAST_ERROR: Mutex<String> = Mutex::new(String::new());
+
+/// Installs the hook that records panics for `last_error`, and the logger if
+/// there is one. Runs once per instance.
+pub(crate) fn install_hook() {
+    INSTALL.call_once(|| {
+        logger::install();
+
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            // the message has to survive the arena being reset
+            arena::outside(|| {
+                if let Ok(mut last_error) = LAST_ERROR.lock() {
+                    *last_error = info.to_string();
+                }
+                previous(info);
+            })
+        }));
+    });
+}
+
+/// Forgets the previous call's panic so `last_error` only ever describes the latest call.
+pub(crate) fn clear_last_error() {
+    if let Ok(mut last_error) = LAST_ERROR.lock() {
+        last_error.clear();
+    }
+}
+
+/// Keeps `message` for `last_error`, for failures that are reported without
+/// trapping.
+pub(crate) fn set_last_error(message: String) {
+    if let Ok(mut last_error) = LAST_ERROR.lock() {
+        *last_error = message;
+    }
+}
+
+/// The message and location of the last panic, e.g.
+/// `panicked at src/lib.rs:10:5:\nattempt to divide by zero`.
+pub fn last_error_message() -> String {
+    LAST_ERROR.lock().map(|last_error| last_error.clone()).unwrap_or_default()
+}
+
+/// Pointer to the UTF-8 text of the last panic, for the host to read after a
+/// trap, or of why `init` rejected its config. Valid until the next call into
+/// the module; its length is `last_error_len()`.
+#[no_mangle]
+pub extern "C" fn last_error() -> *const u8 {
+    LAST_ERROR.lock().map(|last_error| last_error.as_ptr()).unwrap_or(std::ptr::null())
+}
+
+#[no_mangle]
+pub extern "C" fn last_error_len() -> usize {
+    LAST_ERROR.lock().map(|last_error| last_error.len()).unwrap_or(0)
+}
@@ -0,0 +1,108 @@
+use crate::{Args, Error, ErrorCode, FunctionDescription, SelectResults};
+
+/// A function that can be listed in [`export_functions!`]. `#[stardog_function]`
+/// implements it on a hidden empty struct named after the function.
+pub trait Function {
+    /// The name `wasm:call` selects the function by.
+    const NAME: &'static str;
+
+    fn call(args: &Args) -> Result<SelectResults, Error>;
+
+    fn describe() -> FunctionDescription;
+}
+
+/// A registered function: its name and how to call it.
+pub type Entry = (&'static str, fn(&Args) -> Result<SelectResults, Error>);
+
+/// Calls the function named by the first argument with the remaining ones.
+#[doc(hidden)]
+pub fn dispatch(args: &Args, functions: &[Entry]) -> Result<SelectResults, Error> {
+    let name: &str = args.require(0)?;
+    let (_, function) = functions
+        .iter()
+        .find(|(candidate, _)| *candidate == name)
+        .ok_or_else(|| Error::new(ErrorCode::UnknownFunction, format!("no function named `{}`", name)))?;
+
+    function(&args.skip(1))
+}
+
+/// Exports the listed `#[stardog_function]`s from one module.
+///
+/// The module's `evaluate` takes the function name as the first argument, so
+/// `wasm:call(<strings.wasm>, "levenshtein", ?a, ?b)` calls `levenshtein(?a, ?b)`.
+/// A name that is not listed is reported as [`ErrorCode::UnknownFunction`].
+///
+/// The module also exports `describe(output_len) -> result`, which returns a
+/// [`ModuleDescription`](crate::ModuleDescription) as JSON, built from the
+/// functions' signatures and the crate's `Cargo.toml`.
+///
+/// Optional sections may follow the functions, in this order:
+///
+/// ```ignore
+/// export_functions!(similar, distance; init = Settings; aggregates = Median, Mode; tables = Tokens);
+/// ```
+///
+/// `init = State` also exports `init(config, config_len) -> status` for the
+/// [`Init`](crate::Init) state `State`, `aggregates = ...` exports the listed
+/// [`Aggregate`](crate::Aggregate)s and `tables = ...` the listed
+/// [`TableFunction`](crate::TableFunction)s.
+#[macro_export]
+macro_rules! export_functions {
+    (
+        $($function:path),* $(,)?
+        $(; init = $state:ty)?
+        $(; aggregates = $($aggregate:path),+ $(,)?)?
+        $(; tables = $($table:path),+ $(,)?)?
+    ) => {
+        #[doc(hidden)]
+        fn __stardog_wasm_dispatch(args: &$crate::Args) -> ::std::result::Result<$crate::SelectResults, $crate::Error> {
+            $crate::dispatch(args, &[$((<$function as $crate::Function>::NAME, <$function as $crate::Function>::call)),*])
+        }
+
+        $crate::__export_module!(
+            __stardog_wasm_dispatch,
+            $crate::abi::features::DISPATCH
+                | $crate::abi::features::DESCRIBE
+                $(| { let _: ::std::marker::PhantomData<$state>; $crate::abi::features::INIT })?
+                $(| { $(let _: ::std::marker::PhantomData<$aggregate>;)+ $crate::abi::features::AGGREGATE })?
+                $(| { $(let _: ::std::marker::PhantomData<$table>;)+ $crate::abi::features::CURSOR })?
+        );
+
+        /// # Safety
+        ///
+        /// Called by the host with a buffer obtained from `allocate`.
+        #[export_name = "describe"]
+        pub unsafe extern "C" fn __stardog_wasm_describe(output_len: *mut usize) -> *mut u8 {
+            $crate::describe(output_len, $crate::ModuleDescription {
+                name: env!("CARGO_PKG_NAME").to_owned(),
+                version: env!("CARGO_PKG_VERSION").to_owned(),
+                license: env!("CARGO_PKG_LICENSE").to_owned(),
+                description: env!("CARGO_PKG_DESCRIPTION").to_owned(),
+                functions: vec![$(<$function as $crate::Function>::describe()),*],
+                aggregates: vec![$($($crate::AggregateDescription {
+                    name: <$aggregate as $crate::Aggregate>::NAME.to_owned(),
+                    description: <$aggregate as $crate::Aggregate>::DESCRIPTION.to_owned(),
+                }),+)?],
+                tables: vec![$($($crate::TableDescription {
+                    name: <$table as $crate::TableFunction>::NAME.to_owned(),
+                    description: <$table as $crate::TableFunction>::DESCRIPTION.to_owned(),
+                    vars: <$table as $crate::TableFunction>::VARS.iter().map(|&var| var.to_owned()).collect(),
+                }),+)?],
+            })
+        }
+
+        $(
+            /// # Safety
+            ///
+            /// Called by the host with a buffer obtained from `allocate`.
+            #[export_name = "init"]
+            pub unsafe extern "C" fn __stardog_wasm_init(config: *const u8, config_len: usize) -> u32 {
+                $crate::init::<$state>(config, config_len)
+            }
+        )?
+
+        $($crate::__export_aggregates!($($aggregate),+);)?
+
+        $($crate::__export_tables!($($table),+);)?
+    };
+}
@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Term};
+
+/// A SPARQL 1.1 JSON select result, as written by Stardog's `QueryResultWriters`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SelectResults {
+    pub head: Head,
+    pub results: Bindings,
+    /// Set instead of any bindings when the evaluation failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<Error>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Head {
+    pub vars: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub link: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bindings {
+    pub bindings: Vec<Binding>,
+}
+
+/// One solution. Unbound variables are simply absent.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Binding(pub BTreeMap<String, Term<'static>>);
+
+impl SelectResults {
+    pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(vars: I) -> SelectResults {
+        SelectResults {
+            head: Head { vars: vars.into_iter().map(Into::into).collect(), link: Vec::new() },
+            results: Bindings::default(),
+            error: None,
+        }
+    }
+
+    pub fn error(error: Error) -> SelectResults {
+        SelectResults { error: Some(error), ..SelectResults::default() }
+    }
+
+    pub fn push(&mut self, binding: Binding) {
+        self.results.bindings.push(binding);
+    }
+
+    pub fn vars(&self) -> &[String] {
+        &self.head.vars
+    }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.results.bindings
+    }
+}
+
+impl Binding {
+    pub fn new() -> Binding {
+        Binding::default()
+    }
+
+    pub fn with<S: Into<String>>(mut self, var: S, term: Term<'static>) -> Binding {
+        self.0.insert(var.into(), term);
+        self
+    }
+
+    pub fn get(&self, var: &str) -> Option<&Term<'static>> {
+        self.0.get(var)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Term<'static>)> {
+        self.0.iter().map(|(var, term)| (var.as_str(), term))
+    }
+}
@@ -0,0 +1,91 @@
+use std::any::{type_name, Any};
+use std::sync::{Arc, Mutex, PoisonError};
+use serde::de::DeserializeOwned;
+
+use crate::{panic, Error, ErrorCode};
+
+/// State a module sets up once per instance, from the config the host passes
+/// to its `init` export, rather than once per call.
+///
+/// ```ignore
+/// #[derive(Deserialize)]
+/// struct Config {
+///     threshold: f64,
+/// }
+///
+/// struct Settings {
+///     threshold: f64,
+/// }
+///
+/// impl Init for Settings {
+///     type Config = Config;
+///
+///     fn init(config: Config) -> Result<Settings, Error> {
+///         Ok(Settings { threshold: config.threshold })
+///     }
+/// }
+///
+/// #[stardog_function]
+/// fn similar(a: &str, b: &str) -> Result<bool, Error> {
+///     Ok(jaro(a, b) >= state::<Settings>()?.threshold)
+/// }
+///
+/// export_functions!(similar; init = Settings);
+/// ```
+///
+/// The config is JSON. A module configured with RDF takes it as SPARQL JSON
+/// select results, with `type Config = SelectResults`.
+pub trait Init: Sized + Send + Sync + 'static {
+    type Config: DeserializeOwned;
+
+    fn init(config: Self::Config) -> Result<Self, Error>;
+}
+
+static STATE: Mutex<Option<Arc<dyn Any + Send + Sync>>> = Mutex::new(None);
+
+/// The state `init` set up, as an [`ErrorCode::NotInitialized`] error if it
+/// has not been called yet.
+///
+/// The state is shared, so a function holding on to it while the host calls
+/// `init` again keeps seeing the old state until it lets go.
+pub fn state<S: Init>() -> Result<Arc<S>, Error> {
+    let state = STATE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+        .ok_or_else(|| Error::new(ErrorCode::NotInitialized, "the module has not been initialized"))?;
+
+    state.downcast().map_err(|_| {
+        Error::new(ErrorCode::NotInitialized, format!("the module was not initialized with {}", type_name::<S>()))
+    })
+}
+
+/// Replaces the state with one built from the `config_len` bytes of JSON at
+/// `config`. Returns 0 if that worked; otherwise the old state, if any, is
+/// kept and `last_error` says what was wrong with the config.
+///
+/// # Safety
+///
+/// `config` must point to `config_len` readable bytes. Called by the code
+/// [`export_functions!`] generates; not meant to be used directly.
+#[doc(hidden)]
+pub unsafe fn init<S: Init>(config: *const u8, config_len: usize) -> u32 {
+    panic::install_hook();
+    panic::clear_last_error();
+
+    let config = std::slice::from_raw_parts(config, config_len);
+    let state = serde_json::from_slice(config)
+        .map_err(|e| Error::new(ErrorCode::InvalidInput, format!("config is not valid: {}", e)))
+        .and_then(S::init);
+
+    match state {
+        Ok(state) => {
+            *STATE.lock().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(state));
+            0
+        }
+        Err(error) => {
+            panic::set_last_error(error.to_string());
+            1
+        }
+    }
+}
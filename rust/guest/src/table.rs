@@ -0,0 +1,180 @@
+use crate::output::vars;
+use crate::{panic, Args, Error, ErrorCode, Handle, IntoResults, IntoRow, Rows, SelectResults};
+
+/// A function producing any number of rows, for use as a property function or
+/// service, exported with the `tables` section of [`export_functions!`].
+///
+/// Rather than returning every row at once, the host opens a cursor on it
+/// with `cursor_open` and pages through the rows with `cursor_next`, so the
+/// rows never all have to be in memory:
+///
+/// ```ignore
+/// struct Tokens {
+///     tokens: std::vec::IntoIter<(String, usize)>,
+/// }
+///
+/// impl TableFunction for Tokens {
+///     const NAME: &'static str = "tokens";
+///     const VARS: &'static [&'static str] = &["token", "position"];
+///     type Row = (String, usize);
+///
+///     fn open(args: &Args) -> Result<Tokens, Error> {
+///         let text: &str = args.require(0)?;
+///         let tokens: Vec<_> = text.split_whitespace().map(str::to_owned).zip(0..).collect();
+///
+///         Ok(Tokens { tokens: tokens.into_iter() })
+///     }
+///
+///     fn next(&mut self) -> Option<Result<(String, usize), Error>> {
+///         self.tokens.next().map(Ok)
+///     }
+/// }
+/// ```
+pub trait TableFunction: Sized + 'static {
+    /// The name `cursor_open` selects the function by.
+    const NAME: &'static str;
+    /// What `describe` says the function does.
+    const DESCRIPTION: &'static str = "";
+    /// The names of the variables of each row; `result` or `result[0]`,
+    /// `result[1]`, ... if empty, as for a function's results.
+    const VARS: &'static [&'static str] = &[];
+
+    type Row: IntoRow;
+
+    /// Starts a cursor on the rows for `args`, the arguments after the module
+    /// and the function name.
+    fn open(args: &Args) -> Result<Self, Error>;
+
+    /// The next row, or `None` once there are no more.
+    fn next(&mut self) -> Option<Result<Self::Row, Error>>;
+}
+
+/// An open cursor behind a handle, whatever the function.
+trait Cursor {
+    /// Up to `max_rows` of the rows that are left.
+    fn next_page(&mut self, max_rows: usize) -> Result<SelectResults, Error>;
+}
+
+struct Open<T> {
+    function: T,
+    vars: Vec<String>,
+}
+
+impl<T: TableFunction> Cursor for Open<T> {
+    fn next_page(&mut self, max_rows: usize) -> Result<SelectResults, Error> {
+        let mut page = Rows::new(&self.vars);
+
+        while page.len() < max_rows {
+            match self.function.next() {
+                Some(row) => page.push(row?)?,
+                None => break,
+            }
+        }
+
+        page.into_results()
+    }
+}
+
+/// A registered table function: its name and how to open a cursor on it.
+pub type TableEntry = (&'static str, fn(&Args) -> Result<Handle, Error>);
+
+#[doc(hidden)]
+pub fn open_cursor<T: TableFunction>(args: &Args) -> Result<Handle, Error> {
+    let vars = if T::VARS.is_empty() { vars(T::Row::WIDTH) } else { T::VARS.iter().map(|&var| var.to_owned()).collect() };
+    let cursor: Box<dyn Cursor> = Box::new(Open { function: T::open(args)?, vars });
+
+    Ok(Box::into_raw(Box::new(cursor)) as Handle)
+}
+
+/// Opens a cursor on the table function named by the first argument of the
+/// input, encoded as for `evaluate`, with the remaining ones. Returns null
+/// with the reason in `last_error` if that fails.
+///
+/// # Safety
+///
+/// `input` must point to `input_len` readable bytes. Called by the code
+/// [`export_functions!`] generates; not meant to be used directly.
+#[doc(hidden)]
+pub unsafe fn cursor_open(input: *const u8, input_len: usize, tables: &[TableEntry]) -> Handle {
+    panic::install_hook();
+    panic::clear_last_error();
+
+    let input = std::str::from_utf8(std::slice::from_raw_parts(input, input_len));
+    let cursor = crate::decode_input(input, Args::parse).and_then(|args| {
+        let name: &str = args.require(0)?;
+        let (_, open) = tables
+            .iter()
+            .find(|(candidate, _)| *candidate == name)
+            .ok_or_else(|| Error::new(ErrorCode::UnknownFunction, format!("no table function named `{}`", name)))?;
+
+        open(&args.skip(1))
+    });
+
+    cursor.unwrap_or_else(|error| {
+        panic::set_last_error(error.to_string());
+        std::ptr::null_mut()
+    })
+}
+
+/// Returns up to `max_rows` more rows of the cursor the way `evaluate` returns
+/// its results. A page with fewer rows than asked for is the last one; an
+/// error document means the cursor is unusable and should be closed.
+///
+/// # Safety
+///
+/// `handle` must be an open cursor and `output_len` must point to a writable
+/// `usize`.
+#[doc(hidden)]
+pub unsafe fn cursor_next(handle: Handle, max_rows: usize, output_len: *mut usize) -> *mut u8 {
+    panic::install_hook();
+    panic::clear_last_error();
+
+    let cursor = &mut *(handle as *mut Box<dyn Cursor>);
+    let output = crate::result_document(cursor.next_page(max_rows));
+
+    crate::into_sized_result_buffer(output, output_len)
+}
+
+/// Releases a cursor, whether or not all of its rows were read.
+///
+/// # Safety
+///
+/// `handle` must be an open cursor.
+#[doc(hidden)]
+pub unsafe fn cursor_close(handle: Handle) {
+    drop(Box::from_raw(handle as *mut Box<dyn Cursor>));
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_tables {
+    ($($table:path),+) => {
+        const __STARDOG_WASM_TABLES: &[$crate::TableEntry] =
+            &[$((<$table as $crate::TableFunction>::NAME, $crate::open_cursor::<$table>)),+];
+
+        /// # Safety
+        ///
+        /// Called by the host with a buffer obtained from `allocate`.
+        #[export_name = "cursor_open"]
+        pub unsafe extern "C" fn __stardog_wasm_cursor_open(input: *const u8, input_len: usize) -> $crate::Handle {
+            $crate::cursor_open(input, input_len, __STARDOG_WASM_TABLES)
+        }
+
+        /// # Safety
+        ///
+        /// Called by the host with a cursor from `cursor_open` and a buffer
+        /// obtained from `allocate`.
+        #[export_name = "cursor_next"]
+        pub unsafe extern "C" fn __stardog_wasm_cursor_next(cursor: $crate::Handle, max_rows: usize, output_len: *mut usize) -> *mut u8 {
+            $crate::cursor_next(cursor, max_rows, output_len)
+        }
+
+        /// # Safety
+        ///
+        /// Called by the host with a cursor from `cursor_open`.
+        #[export_name = "cursor_close"]
+        pub unsafe extern "C" fn __stardog_wasm_cursor_close(cursor: $crate::Handle) {
+            $crate::cursor_close(cursor)
+        }
+    };
+}
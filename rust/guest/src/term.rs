@@ -0,0 +1,311 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+use serde::de::{Error as _, IgnoredAny, MapAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// An RDF term as it appears in SPARQL 1.1 JSON results, or an RDF-star
+/// quoted triple as SPARQL-star's JSON results write it.
+///
+/// Terms read from the host's input borrow their strings from the input
+/// buffer whenever they need no unescaping, so `'a` is the lifetime of that
+/// buffer; terms built by functions are usually `Term<'static>`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Term<'a> {
+    Iri(Cow<'a, str>),
+    BlankNode(Cow<'a, str>),
+    Literal {
+        lexical: Cow<'a, str>,
+        datatype: Option<Cow<'a, str>>,
+        lang: Option<Cow<'a, str>>,
+    },
+    /// An RDF-star quoted triple, `<< subject predicate object >>`.
+    Triple(Box<Triple<'a>>),
+}
+
+/// The statement a quoted triple term stands for, whose terms may be quoted
+/// triples themselves.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct Triple<'a> {
+    pub subject: Term<'a>,
+    pub predicate: Term<'a>,
+    pub object: Term<'a>,
+}
+
+impl<'a> Triple<'a> {
+    pub fn new(subject: Term<'a>, predicate: Term<'a>, object: Term<'a>) -> Triple<'a> {
+        Triple { subject, predicate, object }
+    }
+
+    /// Copies whatever the triple's terms borrow.
+    pub fn into_owned(self) -> Triple<'static> {
+        Triple { subject: self.subject.into_owned(), predicate: self.predicate.into_owned(), object: self.object.into_owned() }
+    }
+}
+
+impl fmt::Display for Triple<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<< {} {} {} >>", self.subject, self.predicate, self.object)
+    }
+}
+
+impl<'a> Term<'a> {
+    pub fn iri<S: Into<Cow<'a, str>>>(iri: S) -> Term<'a> {
+        Term::Iri(iri.into())
+    }
+
+    pub fn blank_node<S: Into<Cow<'a, str>>>(id: S) -> Term<'a> {
+        Term::BlankNode(id.into())
+    }
+
+    /// A simple literal, with neither datatype nor language tag.
+    pub fn literal<S: Into<Cow<'a, str>>>(lexical: S) -> Term<'a> {
+        Term::Literal { lexical: lexical.into(), datatype: None, lang: None }
+    }
+
+    pub fn typed_literal<S: Into<Cow<'a, str>>, D: Into<Cow<'a, str>>>(lexical: S, datatype: D) -> Term<'a> {
+        Term::Literal { lexical: lexical.into(), datatype: Some(datatype.into()), lang: None }
+    }
+
+    pub fn lang_literal<S: Into<Cow<'a, str>>, L: Into<Cow<'a, str>>>(lexical: S, lang: L) -> Term<'a> {
+        Term::Literal { lexical: lexical.into(), datatype: None, lang: Some(lang.into()) }
+    }
+
+    pub fn triple(subject: Term<'a>, predicate: Term<'a>, object: Term<'a>) -> Term<'a> {
+        Term::Triple(Box::new(Triple::new(subject, predicate, object)))
+    }
+
+    /// Copies whatever the term borrows.
+    pub fn into_owned(self) -> Term<'static> {
+        match self {
+            Term::Iri(iri) => Term::Iri(Cow::Owned(iri.into_owned())),
+            Term::BlankNode(id) => Term::BlankNode(Cow::Owned(id.into_owned())),
+            Term::Literal { lexical, datatype, lang } => Term::Literal {
+                lexical: Cow::Owned(lexical.into_owned()),
+                datatype: datatype.map(|datatype| Cow::Owned(datatype.into_owned())),
+                lang: lang.map(|lang| Cow::Owned(lang.into_owned())),
+            },
+            Term::Triple(triple) => Term::Triple(Box::new(triple.into_owned())),
+        }
+    }
+
+    /// Reads a term the way [`Deserialize`] does, but borrowing from the input
+    /// instead of copying.
+    pub fn deserialize_borrowed<D: Deserializer<'a>>(deserializer: D) -> Result<Term<'a>, D::Error> {
+        Term::try_from(RawTerm::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+
+    /// The IRI, blank node label or lexical form, depending on the kind of
+    /// term, or `None` for a quoted triple, which has none.
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            Term::Iri(iri) => Some(iri),
+            Term::BlankNode(id) => Some(id),
+            Term::Literal { lexical, .. } => Some(lexical),
+            Term::Triple(_) => None,
+        }
+    }
+
+    pub fn is_iri(&self) -> bool {
+        matches!(self, Term::Iri(_))
+    }
+
+    pub fn is_blank_node(&self) -> bool {
+        matches!(self, Term::BlankNode(_))
+    }
+
+    pub fn is_literal(&self) -> bool {
+        matches!(self, Term::Literal { .. })
+    }
+
+    pub fn is_triple(&self) -> bool {
+        matches!(self, Term::Triple(_))
+    }
+
+    pub fn as_triple(&self) -> Option<&Triple<'a>> {
+        match self {
+            Term::Triple(triple) => Some(triple),
+            _ => None,
+        }
+    }
+
+    pub fn datatype(&self) -> Option<&str> {
+        match self {
+            Term::Literal { datatype, .. } => datatype.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn lang(&self) -> Option<&str> {
+        match self {
+            Term::Literal { lang, .. } => lang.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Term<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Term::Iri(iri) => write!(f, "<{}>", iri),
+            Term::BlankNode(id) => write!(f, "_:{}", id),
+            Term::Literal { lexical, datatype, lang } => {
+                write!(f, "{:?}", lexical)?;
+                if let Some(lang) = lang {
+                    write!(f, "@{}", lang)
+                } else if let Some(datatype) = datatype {
+                    write!(f, "^^<{}>", datatype)
+                } else {
+                    Ok(())
+                }
+            }
+            Term::Triple(triple) => triple.fmt(f),
+        }
+    }
+}
+
+/// The wire form of a term, `{"type": ..., "value": ..., ...}`.
+#[derive(Serialize, Deserialize)]
+struct RawTerm<'a> {
+    #[serde(rename = "type", borrow)]
+    kind: Cow<'a, str>,
+    #[serde(borrow)]
+    value: RawValue<'a>,
+    #[serde(default, borrow, deserialize_with = "borrow_optional", skip_serializing_if = "Option::is_none")]
+    datatype: Option<Cow<'a, str>>,
+    #[serde(rename = "xml:lang", default, borrow, deserialize_with = "borrow_optional", skip_serializing_if = "Option::is_none")]
+    lang: Option<Cow<'a, str>>,
+}
+
+/// A term's `value`: a string, or for a quoted triple (`"type": "triple"`)
+/// an object with the `subject`, `predicate` and `object` terms.
+enum RawValue<'a> {
+    Text(Cow<'a, str>),
+    Triple(Box<Triple<'a>>),
+}
+
+impl Serialize for RawValue<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            RawValue::Text(text) => serializer.serialize_str(text),
+            RawValue::Triple(triple) => triple.serialize(serializer),
+        }
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for RawValue<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<RawValue<'a>, D::Error> {
+        deserializer.deserialize_any(RawValueVisitor)
+    }
+}
+
+struct RawValueVisitor;
+
+impl<'de> Visitor<'de> for RawValueVisitor {
+    type Value = RawValue<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string or a quoted triple")
+    }
+
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<RawValue<'de>, E> {
+        Ok(RawValue::Text(Cow::Borrowed(value)))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<RawValue<'de>, E> {
+        Ok(RawValue::Text(Cow::Owned(value.to_owned())))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<RawValue<'de>, E> {
+        Ok(RawValue::Text(Cow::Owned(value)))
+    }
+
+    fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<RawValue<'de>, M::Error> {
+        let (mut subject, mut predicate, mut object) = (None, None, None);
+        while let Some(key) = map.next_key::<Cow<str>>()? {
+            let term = match &*key {
+                "subject" => &mut subject,
+                "predicate" => &mut predicate,
+                "object" => &mut object,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                    continue;
+                }
+            };
+            *term = Some(map.next_value_seed(BorrowedTerm)?);
+        }
+
+        let subject = subject.ok_or_else(|| M::Error::missing_field("subject"))?;
+        let predicate = predicate.ok_or_else(|| M::Error::missing_field("predicate"))?;
+        let object = object.ok_or_else(|| M::Error::missing_field("object"))?;
+
+        Ok(RawValue::Triple(Box::new(Triple::new(subject, predicate, object))))
+    }
+}
+
+struct BorrowedTerm;
+
+impl<'de> serde::de::DeserializeSeed<'de> for BorrowedTerm {
+    type Value = Term<'de>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Term<'de>, D::Error> {
+        Term::deserialize_borrowed(deserializer)
+    }
+}
+
+/// `#[serde(borrow)]` only borrows a `Cow` that is the whole field.
+fn borrow_optional<'de: 'a, 'a, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Cow<'a, str>>, D::Error> {
+    #[derive(Deserialize)]
+    struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+
+    Ok(Option::<Borrowed>::deserialize(deserializer)?.map(|Borrowed(string)| string))
+}
+
+impl<'a> TryFrom<RawTerm<'a>> for Term<'a> {
+    type Error = String;
+
+    fn try_from(raw: RawTerm<'a>) -> Result<Term<'a>, String> {
+        match (&*raw.kind, raw.value) {
+            ("uri", RawValue::Text(iri)) => Ok(Term::Iri(iri)),
+            ("bnode", RawValue::Text(id)) => Ok(Term::BlankNode(id)),
+            // "typed-literal" is what some older writers emit for datatyped literals
+            ("literal" | "typed-literal", RawValue::Text(lexical)) => Ok(Term::Literal { lexical, datatype: raw.datatype, lang: raw.lang }),
+            ("triple", RawValue::Triple(triple)) => Ok(Term::Triple(triple)),
+            ("uri" | "bnode" | "literal" | "typed-literal", RawValue::Triple(_)) => Err(format!("a `{}` term cannot have a triple as its value", raw.kind)),
+            ("triple", RawValue::Text(_)) => Err("a `triple` term must have a triple as its value".to_owned()),
+            (kind, _) => Err(format!("unknown term type `{}`", kind)),
+        }
+    }
+}
+
+impl Serialize for Term<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let raw = match self {
+            Term::Iri(iri) => RawTerm { kind: Cow::Borrowed("uri"), value: RawValue::Text(Cow::Borrowed(iri)), datatype: None, lang: None },
+            Term::BlankNode(id) => RawTerm { kind: Cow::Borrowed("bnode"), value: RawValue::Text(Cow::Borrowed(id)), datatype: None, lang: None },
+            Term::Triple(triple) => {
+                let mut raw = serializer.serialize_struct("RawTerm", 2)?;
+                raw.serialize_field("type", "triple")?;
+                raw.serialize_field("value", triple)?;
+                return raw.end();
+            }
+            Term::Literal { lexical, datatype, lang } => RawTerm {
+                kind: Cow::Borrowed("literal"),
+                value: RawValue::Text(Cow::Borrowed(lexical)),
+                datatype: datatype.as_deref().map(Cow::Borrowed),
+                lang: lang.as_deref().map(Cow::Borrowed),
+            },
+        };
+
+        raw.serialize(serializer)
+    }
+}
+
+/// Copies every string, so that documents of any lifetime can be read into
+/// `Term<'static>`. See [`Term::deserialize_borrowed`] for the other way.
+impl<'de> Deserialize<'de> for Term<'_> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Term::deserialize_borrowed(deserializer).map(Term::into_owned)
+    }
+}
@@ -0,0 +1,36 @@
+//! Every module says which calling convention it speaks and which of its
+//! optional parts it has.
+
+use stardog_wasm_guest::abi::{self, features, Encoding, Framing};
+use stardog_wasm_guest::{export_function, Args};
+
+extern "C" {
+    #[link_name = "stardog_wasm_abi_version"]
+    fn abi_version() -> u32;
+    #[link_name = "stardog_wasm_abi_features"]
+    fn abi_features() -> u32;
+}
+
+fn to_upper(args: &Args) -> Option<String> {
+    args.argument::<&str>(0).map(str::to_uppercase)
+}
+
+export_function!(to_upper);
+
+#[test]
+fn a_single_function_module_speaks_the_current_convention() {
+    let version = unsafe { abi_version() };
+    assert_eq!(version, abi::ABI_VERSION);
+    let convention = abi::convention(version).unwrap();
+    assert_eq!(convention.entry_point, "evaluate");
+    assert_eq!(convention.framing, Framing::LengthDelimited);
+    assert_eq!(convention.encoding, Encoding::SparqlJson);
+    assert!(abi::convention(version + 1).is_none());
+
+    let features = unsafe { abi_features() };
+    assert_eq!(features, abi::BASE_FEATURES);
+    assert!(abi::supports(features, features::LAST_ERROR | features::BATCH | features::BINARY));
+    // without `export_functions!` the first argument is not a function name
+    assert!(!abi::supports(features, features::DISPATCH));
+    assert!(!abi::supports(features, features::DESCRIBE));
+}
@@ -0,0 +1,106 @@
+//! `#[accept(...)]` turns arguments a parameter does not take into type errors
+//! before the function runs.
+
+mod common;
+
+use common::typed;
+use stardog_wasm_guest::{stardog_function, Error, ErrorCode, Function, Term};
+
+#[stardog_function]
+fn length(#[accept(literal)] value: &str) -> usize {
+    value.chars().count()
+}
+
+#[stardog_function]
+fn local_name(#[accept(iri)] iri: &str) -> String {
+    iri.rsplit(['/', '#']).next().unwrap_or_default().to_owned()
+}
+
+#[stardog_function]
+fn shout(#[accept(plain_literal)] value: &str) -> String {
+    value.to_uppercase()
+}
+
+#[stardog_function]
+fn half(#[accept(numeric)] value: f64) -> f64 {
+    value / 2.0
+}
+
+#[stardog_function]
+fn successor(#[accept(numeric)] value: i64) -> i64 {
+    value + 1
+}
+
+#[stardog_function]
+fn year(#[accept(datatype("xsd:date", "xsd:dateTime"))] date: &str) -> String {
+    date[..4].to_owned()
+}
+
+#[stardog_function]
+fn initial(#[accept(datatype("xsd:string"))] value: &str) -> String {
+    value.chars().take(1).collect()
+}
+
+fn call<F: Function>(argument: Term<'static>) -> Result<(), Error> {
+    common::call::<F>(&[argument]).map(drop)
+}
+
+/// The code of the error `F` returns for `argument`, or `None` if it accepts it.
+fn rejects<F: Function>(argument: Term<'static>) -> Option<ErrorCode> {
+    call::<F>(argument).err().map(|error| error.code)
+}
+
+#[test]
+fn literal_takes_any_literal_by_its_lexical_form() {
+    assert_eq!(rejects::<length>(Term::literal("woof")), None);
+    assert_eq!(rejects::<length>(typed("42", "integer")), None);
+    assert_eq!(rejects::<length>(Term::lang_literal("straße", "de")), None);
+    assert_eq!(rejects::<length>(Term::iri("http://example.com/woof")), Some(ErrorCode::TypeError));
+    assert_eq!(rejects::<length>(Term::blank_node("b0")), Some(ErrorCode::TypeError));
+}
+
+#[test]
+fn iri_takes_only_iris() {
+    assert_eq!(rejects::<local_name>(Term::iri("http://example.com/woof")), None);
+    assert_eq!(rejects::<local_name>(Term::literal("http://example.com/woof")), Some(ErrorCode::TypeError));
+}
+
+#[test]
+fn plain_literal_takes_strings() {
+    assert_eq!(rejects::<shout>(Term::literal("woof")), None);
+    assert_eq!(rejects::<shout>(typed("woof", "string")), None);
+    assert_eq!(rejects::<shout>(Term::lang_literal("straße", "de")), None);
+    assert_eq!(rejects::<shout>(typed("42", "integer")), Some(ErrorCode::TypeError));
+}
+
+#[test]
+fn numeric_promotes_but_never_demotes() {
+    for (lexical, datatype) in &[("1", "integer"), ("1", "unsignedByte"), ("1.5", "decimal"), ("1.5", "float"), ("1.5E0", "double")] {
+        assert_eq!(rejects::<half>(typed(lexical, datatype)), None);
+    }
+    assert_eq!(rejects::<half>(Term::literal("1.5")), Some(ErrorCode::TypeError));
+
+    assert_eq!(rejects::<successor>(typed("1", "long")), None);
+    assert_eq!(rejects::<successor>(typed("1.0", "decimal")), Some(ErrorCode::TypeError));
+    assert_eq!(rejects::<successor>(typed("1.0E0", "double")), Some(ErrorCode::TypeError));
+}
+
+#[test]
+fn datatype_takes_the_listed_datatypes() {
+    assert_eq!(rejects::<year>(typed("2021-03-04", "date")), None);
+    assert_eq!(rejects::<year>(typed("2021-03-04T05:06:07Z", "dateTime")), None);
+
+    let error = call::<year>(Term::literal("2021-03-04")).unwrap_err();
+    assert_eq!(error.code, ErrorCode::TypeError);
+    assert!(error.message.contains("<http://www.w3.org/2001/XMLSchema#date> or <http://www.w3.org/2001/XMLSchema#dateTime>"), "{}", error.message);
+    // the host bound the argument to value[1]
+    assert!(error.message.starts_with("value[1] "), "{}", error.message);
+}
+
+#[test]
+fn simple_literals_are_strings() {
+    assert_eq!(rejects::<initial>(Term::literal("woof")), None);
+    assert_eq!(rejects::<initial>(typed("woof", "string")), None);
+    assert_eq!(rejects::<initial>(Term::lang_literal("woof", "en")), Some(ErrorCode::TypeError));
+    assert_eq!(rejects::<initial>(Term::iri("http://example.com/woof")), Some(ErrorCode::TypeError));
+}
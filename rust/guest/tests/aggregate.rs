@@ -0,0 +1,119 @@
+//! An aggregate's state lives in the guest between `agg_` calls, and states
+//! built apart can be merged before the result is taken.
+
+mod common;
+
+use std::ptr;
+use stardog_wasm_guest::{export_functions, last_error_message, Aggregate, Args, Error, Handle, Term};
+
+extern "C" {
+    #[link_name = "agg_init"]
+    fn agg_init(name: *const u8, name_len: usize) -> Handle;
+    #[link_name = "agg_step"]
+    fn agg_step(state: Handle, input: *const u8, input_len: usize) -> u32;
+    #[link_name = "agg_merge"]
+    fn agg_merge(state: Handle, other: Handle) -> u32;
+    #[link_name = "agg_finalize"]
+    fn agg_finalize(state: Handle, output_len: *mut usize) -> *mut u8;
+    #[link_name = "agg_free"]
+    fn agg_free(state: Handle);
+    #[link_name = "stardog_wasm_abi_features"]
+    fn abi_features() -> u32;
+}
+
+#[derive(Default)]
+struct Sum {
+    total: i64,
+}
+
+impl Aggregate for Sum {
+    const NAME: &'static str = "sum";
+    type Output = i64;
+
+    fn step(&mut self, args: &Args) -> Result<(), Error> {
+        self.total += args.require::<i64>(0)?;
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Sum) {
+        self.total += other.total;
+    }
+
+    fn finalize(self) -> i64 {
+        self.total
+    }
+}
+
+#[derive(Default)]
+struct Count {
+    rows: usize,
+}
+
+impl Aggregate for Count {
+    const NAME: &'static str = "count";
+    type Output = usize;
+
+    fn step(&mut self, _: &Args) -> Result<(), Error> {
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Count) {
+        self.rows += other.rows;
+    }
+
+    fn finalize(self) -> usize {
+        self.rows
+    }
+}
+
+export_functions!(; aggregates = Sum, Count);
+
+fn init(name: &str) -> Handle {
+    unsafe { agg_init(name.as_ptr(), name.len()) }
+}
+
+fn step(state: Handle, value: &str) -> u32 {
+    let input = common::input(&[Term::literal("sum"), common::typed(value, "integer")]);
+
+    unsafe { agg_step(state, input.as_ptr(), input.len()) }
+}
+
+fn finalize(state: Handle) -> String {
+    let mut len = 0;
+    let output = unsafe { agg_finalize(state, &mut len) };
+
+    String::from_utf8(unsafe { common::take(output, len) }).unwrap()
+}
+
+#[test]
+fn states_are_stepped_merged_and_finalized_or_freed() {
+    let (a, b) = (init("sum"), init("sum"));
+    for value in &["1", "2", "3"] {
+        assert_eq!(step(a, value), 0);
+    }
+    assert_eq!(step(b, "10"), 0);
+
+    assert_eq!(unsafe { agg_merge(a, b) }, 0);
+    assert!(finalize(a).contains(r#""value":"16""#));
+
+    // `last_error` is shared, so failures are checked in the same test
+    assert_eq!(init("product"), ptr::null_mut());
+    assert!(last_error_message().contains("no aggregate named `product`"));
+
+    let state = init("sum");
+    assert_eq!(step(state, "one"), 1);
+    assert!(last_error_message().contains("value[2]"));
+    assert_eq!(step(state, "1"), 0);
+
+    let count = init("count");
+    assert_eq!(unsafe { agg_merge(state, count) }, 1);
+    assert!(last_error_message().contains("cannot merge"));
+    assert!(finalize(state).contains(r#""value":"1""#));
+
+    let state = init("count");
+    assert_eq!(step(state, "1"), 0);
+    unsafe { agg_free(state) };
+
+    assert_ne!(unsafe { abi_features() } & stardog_wasm_guest::abi::features::AGGREGATE, 0);
+}
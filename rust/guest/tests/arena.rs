@@ -0,0 +1,91 @@
+//! With the `arena` feature every call starts from an empty arena, so an
+//! instance uses the same memory for its millionth call as for its first.
+//!
+//! Run with `cargo test -p stardog-wasm-guest --features arena`.
+
+#![cfg(feature = "arena")]
+
+mod common;
+
+use std::cell::RefCell;
+use stardog_wasm_guest::{export_functions, stardog_function, Term};
+
+extern "C" {
+    #[link_name = "evaluate"]
+    fn evaluate(input: *const u8, input_len: usize, output_len: *mut usize) -> *mut u8;
+    #[link_name = "reset_heap"]
+    fn reset_heap();
+}
+
+#[stardog_function]
+fn repeat(value: &str, times: usize) -> String {
+    value.repeat(times)
+}
+
+thread_local! {
+    static SEEN: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+#[stardog_function]
+fn remember(value: &str) -> usize {
+    SEEN.with(|seen| {
+        let mut seen = seen.borrow_mut();
+        seen.extend_from_slice(value.as_bytes());
+        seen.len()
+    })
+}
+
+export_functions!(repeat, remember);
+
+/// Where the result of repeating "ab" `times` times was.
+fn call(times: usize, free: bool) -> usize {
+    let input = common::input(&[Term::literal("repeat"), Term::literal("ab"), Term::literal(times.to_string())]);
+    let (output, result) = common::evaluate_in_guest_memory(evaluate, input.as_bytes(), !free);
+    assert!(result.contains(&"ab".repeat(times)));
+
+    output
+}
+
+#[test]
+fn every_call_reuses_the_arena() {
+    let first = call(10, true);
+
+    for _ in 0..100_000 {
+        assert_eq!(call(10, true), first);
+    }
+}
+
+#[test]
+fn results_do_not_need_freeing() {
+    let first = call(10, false);
+    unsafe { reset_heap() };
+
+    for _ in 0..100_000 {
+        assert_eq!(call(10, false), first);
+    }
+}
+
+#[test]
+fn calls_larger_than_the_arena_grow_it_once() {
+    call(100_000, true);
+    let first = call(100_000, true);
+
+    for _ in 0..100 {
+        assert_eq!(call(100_000, true), first);
+    }
+}
+
+#[test]
+fn memory_from_before_a_call_stays_out_of_the_arena() {
+    // allocated outside any call, so by the system allocator
+    SEEN.with(|seen| seen.borrow_mut().push(b'>'));
+
+    let value = "ab".repeat(1_000);
+    let input = common::input(&[Term::literal("remember"), Term::literal(value.as_str())]);
+    let (_, result) = common::evaluate_in_guest_memory(evaluate, input.as_bytes(), false);
+    assert!(result.contains(r#""value":"2001""#), "{}", result);
+
+    // the next call resets the arena and fills it again
+    call(100_000, true);
+    SEEN.with(|seen| assert_eq!(*seen.borrow(), format!(">{}", value).into_bytes()));
+}
@@ -0,0 +1,67 @@
+//! `evaluate_batch` runs one call per row and returns their result documents
+//! in order, each failing or succeeding on its own.
+
+mod common;
+
+use serde_json::Value;
+use stardog_wasm_guest::{export_functions, stardog_function, Args};
+
+extern "C" {
+    #[link_name = "evaluate_batch"]
+    fn evaluate_batch(input: *const u8, input_len: usize, output_len: *mut usize) -> *mut u8;
+}
+
+#[stardog_function]
+fn double(n: i64) -> i64 {
+    n * 2
+}
+
+export_functions!(double);
+
+fn row(n: &str) -> String {
+    format!(
+        r#"{{"value[0]":{{"type":"uri","value":"file:///double.wasm"}},"value[1]":{{"type":"literal","value":"double"}},"value[2]":{{"type":"literal","value":"{}"}}}}"#,
+        n
+    )
+}
+
+fn batch(rows: &[&str]) -> String {
+    let rows: Vec<String> = rows.iter().map(|n| row(n)).collect();
+
+    format!(r#"{{"head":{{"vars":["value[0]","value[1]","value[2]"]}},"results":{{"bindings":[{}]}}}}"#, rows.join(","))
+}
+
+fn call(input: &str) -> Value {
+    serde_json::from_slice(&common::evaluate(evaluate_batch, input.as_bytes())).unwrap()
+}
+
+#[test]
+fn rows_are_parsed_into_one_call_each() {
+    let input = batch(&["1", "2"]);
+    let batch = Args::parse_batch(&input).unwrap();
+    assert_eq!(batch.len(), 2);
+    assert_eq!(batch[1].argument::<&str>(0), Some("double"));
+    assert_eq!(batch[1].argument::<i64>(1), Some(2));
+}
+
+#[test]
+fn each_row_gets_its_own_result_document_in_order() {
+    let documents = call(&batch(&["1", "2", "abc", "4"]));
+    let documents = documents.as_array().unwrap();
+    assert_eq!(documents.len(), 4);
+
+    let results: Vec<&Value> = documents.iter().map(|document| &document["results"]["bindings"][0]["result"]["value"]).collect();
+    assert_eq!(results[0], "2");
+    assert_eq!(results[1], "4");
+    assert_eq!(results[3], "8");
+
+    assert_eq!(documents[2]["error"]["code"], "type-error");
+    assert_eq!(documents[2]["results"]["bindings"].as_array().map(Vec::len), Some(0));
+    assert!(documents.iter().enumerate().all(|(i, document)| (i == 2) == document.get("error").is_some()));
+}
+
+#[test]
+fn an_undecodable_batch_is_a_single_error() {
+    let document = call("[");
+    assert_eq!(document["error"]["code"], "invalid-input");
+}
@@ -0,0 +1,56 @@
+//! `evaluate_binary` is `evaluate` in the binary encoding, for hosts that
+//! would rather skip JSON.
+
+mod common;
+
+use stardog_wasm_guest::abi::{self, Encoding};
+use stardog_wasm_guest::{binary, export_functions, stardog_function, ErrorCode, LangString, SelectResults, Term};
+
+extern "C" {
+    #[link_name = "evaluate_binary"]
+    fn evaluate_binary(input: *const u8, input_len: usize, output_len: *mut usize) -> *mut u8;
+    #[link_name = "stardog_wasm_abi_version"]
+    fn abi_version() -> u32;
+    #[link_name = "stardog_wasm_abi_features"]
+    fn abi_features() -> u32;
+}
+
+#[stardog_function]
+fn to_upper(value: LangString) -> LangString {
+    value.map(|value| value.to_uppercase())
+}
+
+export_functions!(to_upper);
+
+fn call(arguments: &[Term]) -> SelectResults {
+    let mut values = vec![Some(Term::iri("file:///woof.wasm")), Some(Term::literal("to_upper"))];
+    values.extend(arguments.iter().cloned().map(Some));
+    let input = binary::encode_arguments(&values);
+
+    binary::decode_results(&common::evaluate(evaluate_binary, &input)).unwrap()
+}
+
+#[test]
+fn arguments_and_results_round_trip() {
+    let results = call(&[Term::lang_literal("straße", "de")]);
+    assert_eq!(results.vars(), ["result"]);
+    assert_eq!(results.bindings()[0].get("result"), Some(&Term::lang_literal("STRASSE", "de")));
+
+    let results = call(&[Term::literal("woof")]);
+    assert_eq!(results.bindings()[0].get("result"), Some(&Term::literal("WOOF")));
+
+    assert_eq!(call(&[]).error.map(|error| error.code), Some(ErrorCode::MissingArgument));
+}
+
+#[test]
+fn the_binary_convention_is_listed_for_modules_that_have_it() {
+    let (version, features) = unsafe { (abi_version(), abi_features()) };
+    let encodings: Vec<Encoding> = abi::conventions(version, features).map(|convention| convention.encoding).collect();
+    assert_eq!(encodings, [Encoding::SparqlJson, Encoding::Binary]);
+
+    let binary = abi::conventions(version, features).find(|convention| convention.encoding == Encoding::Binary).unwrap();
+    assert_eq!(binary.entry_point, "evaluate_binary");
+    assert_eq!(binary.feature, abi::features::BINARY);
+
+    assert_eq!(abi::conventions(version, 0).count(), 1);
+}
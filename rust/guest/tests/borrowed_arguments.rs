@@ -0,0 +1,91 @@
+//! String arguments are read straight out of the input buffer: decoding a call
+//! allocates the list of arguments and nothing per argument.
+
+#![cfg(not(feature = "arena"))]
+
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use stardog_wasm_guest::{binary, Args, Term};
+
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn allocations<T, F: FnOnce() -> T>(f: F) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let value = f();
+
+    (value, ALLOCATIONS.with(Cell::get) - before)
+}
+
+fn borrows_from(value: &str, input: &[u8]) -> bool {
+    input.as_ptr_range().contains(&value.as_ptr())
+}
+
+fn input() -> String {
+    common::input(&[Term::literal("knowledge"), Term::lang_literal("graph", "en")])
+}
+
+#[test]
+fn json_arguments_borrow_from_the_input() {
+    let input = input();
+    let ((a, b), count) = allocations(|| {
+        let args = Args::parse(&input).unwrap();
+        let a: &str = args.require(0).unwrap();
+        let b: &str = args.require(1).unwrap();
+        assert!(borrows_from(a, input.as_bytes()));
+        assert!(borrows_from(b, input.as_bytes()));
+
+        (a.len(), b.len())
+    });
+
+    assert_eq!((a, b), ("knowledge".len(), "graph".len()));
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn binary_arguments_borrow_from_the_input() {
+    let input = binary::encode_arguments(&[
+        Some(Term::iri("file:///strings.wasm")),
+        Some(Term::literal("knowledge")),
+        Some(Term::literal("graph")),
+    ]);
+
+    let (_, count) = allocations(|| {
+        let args = binary::decode_arguments(&input).unwrap();
+        let a: &str = args.require(0).unwrap();
+        let b: &str = args.require(1).unwrap();
+        assert!(borrows_from(a, &input));
+        assert!(borrows_from(b, &input));
+    });
+
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn escaped_strings_are_unescaped() {
+    let input = input().replace("knowledge", r#"know\"ledge"#);
+    let args = Args::parse(&input).unwrap();
+
+    assert_eq!(args.value(0), Some("know\"ledge"));
+    assert!(!borrows_from(args.value(0).unwrap(), input.as_bytes()));
+    assert!(borrows_from(args.value(1).unwrap(), input.as_bytes()));
+}
@@ -0,0 +1,77 @@
+//! What the tests share: the input a host sends for a call, and taking the
+//! result it gets back.
+
+// each test uses only some of it
+#![allow(dead_code)]
+
+use std::mem;
+use stardog_wasm_guest::{allocate, deallocate, free_result, Args, Error, Function, SelectResults, Term, XSD};
+
+/// The signature of `evaluate` and the other exports that take an input and
+/// hand back a result.
+pub type EntryPoint = unsafe extern "C" fn(*const u8, usize, *mut usize) -> *mut u8;
+
+pub fn typed(lexical: &str, datatype: &str) -> Term<'static> {
+    Term::typed_literal(lexical.to_owned(), format!("{}{}", XSD, datatype))
+}
+
+/// The SPARQL JSON for one call: the module's IRI as `value[0]`, then
+/// `arguments`.
+pub fn input(arguments: &[Term]) -> String {
+    let mut values = vec![r#"{"value[0]":{"type":"uri","value":"file:///test.wasm"}}"#.to_owned()];
+    for (i, argument) in arguments.iter().enumerate() {
+        values.push(format!(r#"{{"value[{}]":{}}}"#, i + 1, serde_json::to_string(argument).unwrap()));
+    }
+
+    format!(r#"{{"head":{{"vars":[]}},"results":{{"bindings":[{}]}}}}"#, values.join(","))
+}
+
+/// Calls `F` directly with `arguments`, without going through an export.
+pub fn call<F: Function>(arguments: &[Term]) -> Result<SelectResults, Error> {
+    F::call(&Args::parse(&input(arguments)).unwrap())
+}
+
+/// Copies out a result the guest handed over and releases it.
+///
+/// # Safety
+///
+/// `output` and `len` must be a result that has not been released yet.
+pub unsafe fn take(output: *mut u8, len: usize) -> Vec<u8> {
+    let result = std::slice::from_raw_parts(output, len).to_vec();
+    free_result(output as *mut _, len);
+
+    result
+}
+
+/// Calls `entry_point` with `input` and takes its result.
+pub fn evaluate(entry_point: EntryPoint, input: &[u8]) -> Vec<u8> {
+    unsafe {
+        let mut len = 0;
+        let output = entry_point(input.as_ptr(), input.len(), &mut len);
+        take(output, len)
+    }
+}
+
+/// Calls `entry_point` as `Call.java` does, with the input and the length of
+/// the result in memory from `allocate`, and releases both afterwards. The
+/// result is released too unless `keep` is set. Returns where the result was
+/// and what it said.
+pub fn evaluate_in_guest_memory(entry_point: EntryPoint, input: &[u8], keep: bool) -> (usize, String) {
+    unsafe {
+        let buffer = allocate(input.len()) as *mut u8;
+        buffer.copy_from_nonoverlapping(input.as_ptr(), input.len());
+        let output_len = allocate(mem::size_of::<usize>()) as *mut usize;
+
+        let output = entry_point(buffer, input.len(), output_len);
+        let len = output_len.read_unaligned();
+        let result = std::str::from_utf8(std::slice::from_raw_parts(output, len)).unwrap().to_owned();
+
+        if !keep {
+            free_result(output as *mut _, len);
+        }
+        deallocate(output_len as *mut _, mem::size_of::<usize>());
+        deallocate(buffer as *mut _, input.len());
+
+        (output as usize, result)
+    }
+}
@@ -0,0 +1,109 @@
+//! Optional and variadic parameters, results with unbound values, and the
+//! derives for user structs.
+
+mod common;
+
+use stardog_wasm_guest::{stardog_function, ErrorCode, Function, FromArgument, IntoResult, IntoRow, SelectResults, Term, XSD};
+
+fn integer(value: i64) -> Term<'static> {
+    Term::typed_literal(value.to_string(), format!("{}integer", XSD))
+}
+
+fn call<F: Function>(arguments: &[Term<'static>]) -> Result<SelectResults, ErrorCode> {
+    common::call::<F>(arguments).map_err(|error| error.code)
+}
+
+#[stardog_function]
+fn greet(name: Option<&str>) -> String {
+    format!("hello, {}", name.unwrap_or("world"))
+}
+
+#[stardog_function]
+fn sum(first: i64, rest: Vec<i64>) -> i64 {
+    first + rest.iter().sum::<i64>()
+}
+
+#[test]
+fn options_may_be_left_out_and_vecs_take_the_rest() {
+    let result = call::<greet>(&[]).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&Term::literal("hello, world")));
+    let result = call::<greet>(&[Term::literal("wasm")]).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&Term::literal("hello, wasm")));
+
+    let result = call::<sum>(&[integer(1), integer(2), integer(3)]).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&integer(6)));
+    let result = call::<sum>(&[integer(1)]).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&integer(1)));
+    assert_eq!(call::<sum>(&[integer(1), Term::literal("two")]).unwrap_err(), ErrorCode::TypeError);
+    assert_eq!(call::<sum>(&[]).unwrap_err(), ErrorCode::MissingArgument);
+
+    let parameters = greet::describe().parameters;
+    assert!(parameters[0].optional && !parameters[0].variadic);
+    let parameters = sum::describe().parameters;
+    assert!(!parameters[0].variadic && parameters[1].variadic);
+    assert_eq!(parameters[1].kind, format!("{}integer", XSD));
+}
+
+#[stardog_function]
+fn first_digit(value: &str) -> Option<u32> {
+    value.chars().find_map(|c| c.to_digit(10))
+}
+
+#[stardog_function]
+fn split_once(value: &str) -> (String, Option<String>) {
+    match value.split_once(' ') {
+        Some((first, rest)) => (first.to_owned(), Some(rest.to_owned())),
+        None => (value.to_owned(), None),
+    }
+}
+
+#[test]
+fn none_leaves_the_result_unbound() {
+    let result = call::<first_digit>(&[Term::literal("abc4")]).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&integer(4)));
+    let result = call::<first_digit>(&[Term::literal("abc")]).unwrap();
+    assert_eq!(result.bindings().len(), 1);
+    assert_eq!(result.bindings()[0].get("result"), None);
+
+    let result = call::<split_once>(&[Term::literal("single")]).unwrap();
+    assert_eq!(result.bindings()[0].get("result[0]"), Some(&Term::literal("single")));
+    assert_eq!(result.bindings()[0].get("result[1]"), None);
+}
+
+#[derive(FromArgument)]
+struct Celsius(f64);
+
+#[derive(IntoResult)]
+struct Fahrenheit {
+    degrees: f64,
+}
+
+#[stardog_function]
+fn to_fahrenheit(celsius: Celsius) -> Fahrenheit {
+    Fahrenheit { degrees: celsius.0 * 9.0 / 5.0 + 32.0 }
+}
+
+#[derive(IntoRow)]
+struct Token<'a> {
+    text: &'a str,
+    position: usize,
+    digit: Option<u32>,
+}
+
+#[stardog_function]
+fn tokens(text: &str) -> Vec<Token<'_>> {
+    text.split_whitespace().enumerate().map(|(position, text)| Token { text, position, digit: text.parse().ok() }).collect()
+}
+
+#[test]
+fn structs_derive_their_mapping() {
+    let result = call::<to_fahrenheit>(&[integer(100)]).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&Term::typed_literal("2.12E2", format!("{}double", XSD))));
+    assert_eq!(to_fahrenheit::describe().parameters[0].kind, format!("{}double", XSD));
+
+    let result = call::<tokens>(&[Term::literal("a 7")]).unwrap();
+    assert_eq!(result.vars(), ["result[0]", "result[1]", "result[2]"]);
+    assert_eq!(result.bindings()[1].get("result[0]"), Some(&Term::literal("7")));
+    assert_eq!(result.bindings()[1].get("result[2]"), Some(&integer(7)));
+    assert_eq!(result.bindings()[0].get("result[2]"), None);
+}
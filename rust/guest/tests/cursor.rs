@@ -0,0 +1,85 @@
+//! A table function's rows are paged through a cursor that lives in the guest
+//! between calls, however many rows there are.
+
+mod common;
+
+use std::ptr;
+use stardog_wasm_guest::{export_functions, last_error_message, Args, Error, Handle, TableFunction, Term};
+
+extern "C" {
+    #[link_name = "cursor_open"]
+    fn cursor_open(input: *const u8, input_len: usize) -> Handle;
+    #[link_name = "cursor_next"]
+    fn cursor_next(cursor: Handle, max_rows: usize, output_len: *mut usize) -> *mut u8;
+    #[link_name = "cursor_close"]
+    fn cursor_close(cursor: Handle);
+    #[link_name = "stardog_wasm_abi_features"]
+    fn abi_features() -> u32;
+}
+
+/// Counts up from zero, forever unless given where to stop.
+struct Count {
+    next: u64,
+    end: Option<u64>,
+}
+
+impl TableFunction for Count {
+    const NAME: &'static str = "count";
+    const VARS: &'static [&'static str] = &["n", "square"];
+    type Row = (u64, u64);
+
+    fn open(args: &Args) -> Result<Count, Error> {
+        Ok(Count { next: 0, end: args.argument(0) })
+    }
+
+    fn next(&mut self) -> Option<Result<(u64, u64), Error>> {
+        if Some(self.next) == self.end {
+            return None;
+        }
+
+        self.next += 1;
+        Some(Ok((self.next - 1, (self.next - 1) * (self.next - 1))))
+    }
+}
+
+export_functions!(; tables = Count);
+
+fn open(name: &str, end: Option<u64>) -> Handle {
+    let mut arguments = vec![Term::literal(name)];
+    arguments.extend(end.map(|end| Term::literal(end.to_string())));
+    let input = common::input(&arguments);
+
+    unsafe { cursor_open(input.as_ptr(), input.len()) }
+}
+
+fn next(cursor: Handle, max_rows: usize) -> serde_json::Value {
+    let mut len = 0;
+    let output = unsafe { cursor_next(cursor, max_rows, &mut len) };
+
+    serde_json::from_slice(&unsafe { common::take(output, len) }).unwrap()
+}
+
+#[test]
+fn cursors_page_through_the_rows() {
+    let cursor = open("count", Some(5));
+    let page = next(cursor, 2);
+    assert_eq!(page["head"]["vars"], serde_json::json!(["n", "square"]));
+    assert_eq!(page["results"]["bindings"][1]["square"]["value"], "1");
+    assert_eq!(next(cursor, 2)["results"]["bindings"][1]["n"]["value"], "3");
+    assert_eq!(next(cursor, 2)["results"]["bindings"].as_array().unwrap().len(), 1);
+    assert_eq!(next(cursor, 2)["results"]["bindings"].as_array().unwrap().len(), 0);
+    unsafe { cursor_close(cursor) };
+
+    // an unbounded cursor only computes the rows that are asked for
+    let cursor = open("count", None);
+    for page in 0..1_000 {
+        let n = page * 100 + 99;
+        assert_eq!(next(cursor, 100)["results"]["bindings"][99]["n"]["value"], n.to_string());
+    }
+    unsafe { cursor_close(cursor) };
+
+    assert_eq!(open("sum", None), ptr::null_mut());
+    assert!(last_error_message().contains("no table function named `sum`"));
+
+    assert_ne!(unsafe { abi_features() } & stardog_wasm_guest::abi::features::CURSOR, 0);
+}
@@ -0,0 +1,151 @@
+//! Dates, times and durations read every lexical form, write canonical ones
+//! and order and add up as XPath does.
+
+mod common;
+
+use common::typed;
+use stardog_wasm_guest::{stardog_function, Date, DateTime, Duration, ErrorCode, Function, GYear, IntoResult, SelectResults, Term, Time, Timezone, XSD};
+
+fn date_time(lexical: &str) -> DateTime {
+    DateTime::parse(lexical).unwrap()
+}
+
+fn date(lexical: &str) -> Date {
+    Date::parse(lexical).unwrap()
+}
+
+fn time(lexical: &str) -> Time {
+    Time::parse(lexical).unwrap()
+}
+
+fn duration(lexical: &str) -> Duration {
+    Duration::parse(lexical).unwrap()
+}
+
+#[test]
+fn lexical_forms_are_read_and_written_canonically() {
+    assert_eq!(date_time("2021-03-04T05:06:07.500Z").to_string(), "2021-03-04T05:06:07.5Z");
+    assert_eq!(date_time(" 2021-03-04T05:06:07-05:30 ").to_string(), "2021-03-04T05:06:07-05:30");
+    assert_eq!(date_time("2020-12-31T24:00:00").to_string(), "2021-01-01T00:00:00");
+    assert_eq!(date_time("-0044-03-15T12:00:00+00:00").to_string(), "-0044-03-15T12:00:00Z");
+    assert_eq!(date_time("12021-01-01T00:00:00").year(), 12021);
+    assert!(DateTime::parse("2021-02-29T00:00:00").is_none());
+    assert!(DateTime::parse("2021-01-01T24:00:01").is_none());
+    assert!(DateTime::parse("2021-01-01").is_none());
+    assert!(DateTime::parse("02021-01-01T00:00:00").is_none());
+    assert!(DateTime::parse("2021-01-01T00:00:00+14:30").is_none());
+    assert!(DateTime::parse("2021-01-01T00:00:00.").is_none());
+
+    assert_eq!(date("2020-02-29+01:00").to_string(), "2020-02-29+01:00");
+    assert_eq!(date("2020-02-29+01:00").timezone(), Timezone::from_minutes(60));
+    assert!(Date::parse("2020-2-29").is_none());
+    assert_eq!(time("23:59:59.50").to_string(), "23:59:59.5");
+    assert_eq!(time("24:00:00Z").to_string(), "00:00:00Z");
+    assert_eq!(GYear::parse("0000").unwrap().to_string(), "0000");
+    assert!(GYear::parse("99").is_none());
+
+    assert_eq!(duration("P1Y14M").to_string(), "P2Y2M");
+    assert_eq!(duration("-PT36H").to_string(), "-P1DT12H");
+    assert_eq!(duration("P0Y").to_string(), "PT0S");
+    assert_eq!(duration("PT90.250S").to_string(), "PT1M30.25S");
+    assert!(Duration::parse("P").is_none());
+    assert!(Duration::parse("P1DT").is_none());
+    assert!(Duration::parse("PT1D").is_none());
+    assert!(Duration::parse("P1M1Y").is_none());
+    assert!(Duration::parse("P-1D").is_none());
+    assert!(Duration::parse_day_time("P1M").is_none());
+    assert!(Duration::parse_day_time("PT1M").is_some());
+    assert!(Duration::parse_year_month("P1D").is_none());
+}
+
+#[test]
+fn values_are_ordered_across_timezones() {
+    assert_eq!(date_time("2021-01-01T12:00:00+02:00"), date_time("2021-01-01T10:00:00Z"));
+    assert!(date_time("2021-01-01T12:00:00+02:00") < date_time("2021-01-01T11:00:00Z"));
+    assert_eq!(date_time("2021-01-01T12:00:00+02:00").with_timezone(Timezone::UTC).unwrap().to_string(), "2021-01-01T10:00:00Z");
+
+    // without a timezone it could be anywhere within 14 hours of UTC
+    let local = date_time("2021-01-01T12:00:00");
+    assert!(local.partial_cmp(&date_time("2021-01-01T20:00:00Z")).is_none());
+    assert!(local < date_time("2021-01-02T03:00:00Z"));
+    assert!(local > date_time("2020-12-31T21:00:00Z"));
+
+    assert!(date("2021-01-01") < date("2021-01-02"));
+    assert!(time("23:00:00+01:00") < time("23:00:00Z"));
+    assert!(GYear::parse("-0001").unwrap() < GYear::parse("0000").unwrap());
+
+    assert!(duration("P1Y") > duration("P11M"));
+    assert!(duration("P1D") < duration("PT25H"));
+    assert!(duration("P1M").partial_cmp(&duration("P30D")).is_none());
+    assert!(duration("P1M") < duration("P32D"));
+}
+
+#[test]
+fn arithmetic_follows_xpath() {
+    assert_eq!(date_time("2021-01-31T10:00:00Z") + duration("P1M"), date_time("2021-02-28T10:00:00Z"));
+    assert_eq!(date_time("2020-02-29T10:00:00Z") + duration("P1Y"), date_time("2021-02-28T10:00:00Z"));
+    assert_eq!((date_time("2021-12-31T23:30:00") + duration("PT45M")).to_string(), "2022-01-01T00:15:00");
+    assert_eq!((date_time("2021-03-01T00:00:00") - duration("P1D")).to_string(), "2021-02-28T00:00:00");
+
+    let between = date_time("2021-01-02T00:00:00+01:00") - date_time("2021-01-01T00:00:00Z");
+    assert_eq!(between.to_string(), "PT23H");
+    assert_eq!(between.datatype(), format!("{}dayTimeDuration", XSD));
+    assert_eq!((date("2021-03-01") - date("2020-03-01")).to_string(), "P365D");
+    assert_eq!((date("2021-01-31") + duration("P1M1D")).to_string(), "2021-03-01");
+    assert_eq!((time("23:00:00") + duration("PT2H")).to_string(), "01:00:00");
+    assert_eq!((time("01:00:00") - time("23:00:00")).to_string(), "-PT22H");
+
+    assert_eq!(duration("P1Y").checked_add(duration("P2M")), Some(duration("P1Y2M")));
+    assert_eq!(duration("P1D").checked_sub(duration("PT1H")), Some(duration("PT23H")));
+    assert!(duration("P1M").checked_sub(duration("P1D")).is_none());
+    assert_eq!((-duration("P1Y")).to_string(), "-P1Y");
+}
+
+#[test]
+fn arithmetic_past_the_last_year_is_none() {
+    let far = date("2020-01-01") + duration("P700000000000000000Y");
+    assert_eq!(far.year(), 700_000_000_000_002_020);
+    assert_eq!(far - duration("P700000000000000000Y"), date("2020-01-01"));
+
+    let last = DateTime::new(i64::MAX, 12, 31, 23, 0, 0, 0, Timezone::from_minutes(-60)).unwrap();
+    assert!(last.checked_add(duration("PT1H")).is_none());
+    assert!(last.with_timezone(Timezone::UTC).is_none());
+    assert!(date("2020-01-01").checked_add(Duration::from_months(i64::MAX)).is_some());
+    assert!(date("2020-01-01").checked_sub(Duration::from_nanos(i128::MAX)).is_none());
+    assert!(DateTime::parse("9223372036854775807-12-31T24:00:00").is_none());
+    assert!(Date::parse("9223372036854775808-01-01").is_none());
+
+    assert!(Duration::new(1, i128::MAX).unwrap().partial_cmp(&Duration::from_months(2)).is_none());
+    assert_eq!(time("12:00:00") - Duration::from_nanos(i128::MIN), time("12:00:00") + Duration::from_nanos(i128::MAX) + Duration::from_nanos(1));
+}
+
+/// The number of whole days from one date to another.
+#[stardog_function]
+fn days_between(from: Date, to: Date) -> i64 {
+    ((to - from).nanos() / 86_400_000_000_000) as i64
+}
+
+#[stardog_function]
+fn add(instant: DateTime, duration: Duration) -> Option<DateTime> {
+    instant.checked_add(duration)
+}
+
+fn call<F: Function>(arguments: &[Term<'static>]) -> Result<SelectResults, ErrorCode> {
+    common::call::<F>(arguments).map_err(|error| error.code)
+}
+
+#[test]
+fn parameters_and_results_are_typed_literals() {
+    let result = call::<days_between>(&[typed("2020-01-01", "date"), typed("2021-01-01", "date")]).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&typed("366", "integer")));
+    assert!(call::<days_between>(&[Term::literal("2020-01-01"), typed("2021-01-01", "date")]).is_ok());
+    assert_eq!(call::<days_between>(&[typed("2020-01-01T00:00:00", "dateTime"), typed("2021-01-01", "date")]).unwrap_err(), ErrorCode::TypeError);
+
+    let result = call::<add>(&[typed("2021-01-01T00:00:00Z", "dateTimeStamp"), typed("P1Y2M", "yearMonthDuration")]).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&typed("2022-03-01T00:00:00Z", "dateTime")));
+    assert_eq!(call::<add>(&[typed("2021-01-01T00:00:00Z", "dateTime"), typed("P1D", "yearMonthDuration")]).unwrap_err(), ErrorCode::TypeError);
+
+    assert_eq!(duration("P1Y").into_result(), Ok(typed("P1Y", "yearMonthDuration")));
+    assert_eq!(duration("P1YT1S").into_result(), Ok(typed("P1YT1S", "duration")));
+    assert_eq!(Date::new(2021, 2, 3, None).unwrap().into_result(), Ok(typed("2021-02-03", "date")));
+}
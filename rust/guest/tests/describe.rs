@@ -0,0 +1,74 @@
+//! `describe` tells the host what a module's functions take and return,
+//! from their signatures, doc comments and the crate's `Cargo.toml`.
+
+mod common;
+
+use serde_json::{json, Value};
+use stardog_wasm_guest::{export_functions, stardog_function, ModuleDescription};
+
+extern "C" {
+    #[link_name = "describe"]
+    fn describe(output_len: *mut usize) -> *mut u8;
+}
+
+/// The Jaro similarity of two strings.
+#[stardog_function]
+fn jaro(left: &str, right: &str) -> f64 {
+    if left == right {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// A random integer below `bound`, or below 100.
+#[stardog_function(nondeterministic)]
+fn random(bound: Option<i64>, tags: Vec<&str>) -> i64 {
+    let _ = tags;
+    bound.unwrap_or(100) - 1
+}
+
+export_functions!(jaro, random);
+
+fn read_description() -> Value {
+    unsafe {
+        let mut len = 0;
+        let output = describe(&mut len);
+        serde_json::from_slice(&common::take(output, len)).unwrap()
+    }
+}
+
+#[test]
+fn the_description_follows_the_signatures() {
+    let description = read_description();
+    assert_eq!(description["name"], env!("CARGO_PKG_NAME"));
+    assert_eq!(description["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(description["license"], "Apache-2.0");
+
+    let jaro = &description["functions"][0];
+    assert_eq!(jaro["name"], "jaro");
+    assert_eq!(jaro["description"], "The Jaro similarity of two strings.");
+    assert_eq!(jaro["deterministic"], true);
+    assert_eq!(
+        jaro["parameters"],
+        json!([
+            { "name": "left", "type": "http://www.w3.org/2001/XMLSchema#string" },
+            { "name": "right", "type": "http://www.w3.org/2001/XMLSchema#string" },
+        ])
+    );
+    assert_eq!(jaro["returns"], "http://www.w3.org/2001/XMLSchema#double");
+
+    let random = &description["functions"][1];
+    assert_eq!(random["name"], "random");
+    assert_eq!(random["deterministic"], false);
+    assert_eq!(
+        random["parameters"],
+        json!([
+            { "name": "bound", "type": "http://www.w3.org/2001/XMLSchema#integer", "optional": true },
+            { "name": "tags", "type": "http://www.w3.org/2001/XMLSchema#string", "variadic": true },
+        ])
+    );
+    assert_eq!(random["returns"], "http://www.w3.org/2001/XMLSchema#integer");
+
+    assert!(serde_json::from_value::<ModuleDescription>(description).is_ok());
+}
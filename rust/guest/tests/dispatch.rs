@@ -0,0 +1,32 @@
+//! The first argument of a module with several functions picks the one that
+//! is called with the rest.
+
+mod common;
+
+use stardog_wasm_guest::{dispatch, stardog_function, Args, Error, ErrorCode, Function, SelectResults, Term};
+
+#[stardog_function]
+fn to_upper(value: &str) -> String {
+    value.to_uppercase()
+}
+
+#[stardog_function]
+fn to_lower(value: &str) -> String {
+    value.to_lowercase()
+}
+
+fn call(name: &str, value: &str) -> Result<SelectResults, Error> {
+    let input = common::input(&[Term::literal(name), Term::literal(value)]);
+
+    dispatch(&Args::parse(&input).unwrap(), &[(to_upper::NAME, to_upper::call), (to_lower::NAME, to_lower::call)])
+}
+
+#[test]
+fn the_first_argument_names_the_function() {
+    assert_eq!(call("to_upper", "Woof").unwrap().bindings()[0].get("result"), Some(&Term::literal("WOOF")));
+    assert_eq!(call("to_lower", "Woof").unwrap().bindings()[0].get("result"), Some(&Term::literal("woof")));
+
+    let error = call("to_title", "Woof").unwrap_err();
+    assert_eq!(error.code, ErrorCode::UnknownFunction);
+    assert!(error.message.contains("to_title"));
+}
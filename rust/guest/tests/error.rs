@@ -0,0 +1,47 @@
+//! A call that fails still returns a document, with no bindings and an
+//! `error` member saying why.
+
+mod common;
+
+use serde_json::{json, Value};
+use stardog_wasm_guest::{export_functions, stardog_function, Term};
+
+extern "C" {
+    #[link_name = "evaluate"]
+    fn evaluate(input: *const u8, input_len: usize, output_len: *mut usize) -> *mut u8;
+}
+
+#[stardog_function]
+fn double(n: i64) -> i64 {
+    n * 2
+}
+
+export_functions!(double);
+
+fn call(input: &[u8]) -> Value {
+    serde_json::from_slice(&common::evaluate(evaluate, input)).unwrap()
+}
+
+fn call_with(arguments: &[&str]) -> Value {
+    let arguments: Vec<Term> = arguments.iter().map(|&argument| Term::literal(argument)).collect();
+
+    call(common::input(&arguments).as_bytes())
+}
+
+fn assert_error(document: &Value, code: &str) {
+    assert_eq!(document["head"], json!({ "vars": [] }));
+    assert_eq!(document["results"], json!({ "bindings": [] }));
+    assert_eq!(document["error"]["code"], code);
+    assert!(document["error"]["message"].as_str().is_some_and(|message| !message.is_empty()));
+}
+
+#[test]
+fn failures_are_reported_in_the_error_envelope() {
+    assert_eq!(call_with(&["double", "21"])["results"]["bindings"][0]["result"]["value"], "42");
+    assert!(call_with(&["double", "21"]).get("error").is_none());
+
+    assert_error(&call(b"not json"), "invalid-input");
+    assert_error(&call(b"\xff\xfe"), "invalid-input");
+    assert_error(&call_with(&["double"]), "missing-argument");
+    assert_error(&call_with(&["double", "abc"]), "type-error");
+}
@@ -0,0 +1,80 @@
+//! Evaluating a function must leave the heap exactly as it found it once the
+//! host has released the input and the result, so that linear memory never
+//! has to grow however many rows an instance evaluates.
+//!
+//! Natively this counts what the global allocator hands out. Built with
+//! `cargo test --target wasm32-unknown-unknown --test free_result --no-run`,
+//! the test also checks `memory.size`; the module has no imports, so any
+//! runtime can call its `main` export, which traps if the test fails.
+
+#![cfg(not(feature = "arena"))]
+
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use stardog_wasm_guest::{export_functions, stardog_function, Term};
+
+struct Counting;
+
+// per thread, so the test harness's own allocations do not count
+thread_local! {
+    static LIVE: Cell<isize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.with(|live| live.set(live.get() + layout.size() as isize));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        LIVE.with(|live| live.set(live.get() - layout.size() as isize));
+        System.dealloc(pointer, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+extern "C" {
+    #[link_name = "evaluate"]
+    fn evaluate(input: *const u8, input_len: usize, output_len: *mut usize) -> *mut u8;
+}
+
+#[stardog_function]
+fn to_upper(value: &str) -> String {
+    value.to_uppercase()
+}
+
+export_functions!(to_upper);
+
+/// The bytes still allocated and, on wasm32, the pages of linear memory.
+fn heap() -> (isize, usize) {
+    #[cfg(target_arch = "wasm32")]
+    let pages = core::arch::wasm32::memory_size(0);
+    #[cfg(not(target_arch = "wasm32"))]
+    let pages = 0;
+
+    (LIVE.with(Cell::get), pages)
+}
+
+fn call(input: &str) {
+    let (_, result) = common::evaluate_in_guest_memory(evaluate, input.as_bytes(), false);
+    assert!(result.contains("STARDOG"));
+}
+
+#[test]
+fn a_million_calls_grow_neither_the_heap_nor_linear_memory() {
+    let input = common::input(&[Term::literal("to_upper"), Term::literal("stardog")]);
+
+    // the first call installs the panic hook, which lives for the whole instance
+    call(&input);
+    let baseline = heap();
+
+    for _ in 0..1_000_000 {
+        call(&input);
+    }
+
+    assert_eq!(heap(), baseline);
+}
@@ -0,0 +1,85 @@
+//! With the `host-log` feature functions log nothing until the host sets a
+//! level with `set_log_level`, and then keep their records for the host to
+//! take through `next_log_record`.
+//!
+//! Run with `cargo test -p stardog-wasm-guest --features host-log`.
+
+#![cfg(feature = "host-log")]
+
+mod common;
+
+use log::LevelFilter;
+use stardog_wasm_guest::{export_functions, stardog_function, Term};
+
+extern "C" {
+    #[link_name = "evaluate"]
+    fn evaluate(input: *const u8, input_len: usize, output_len: *mut usize) -> *mut u8;
+    #[link_name = "set_log_level"]
+    fn set_log_level(level: u32);
+    #[link_name = "next_log_record"]
+    fn next_log_record(output_len: *mut usize) -> *mut u8;
+    #[link_name = "stardog_wasm_abi_features"]
+    fn abi_features() -> u32;
+}
+
+#[stardog_function]
+fn shout(value: &str) -> String {
+    log::debug!("shouting {}", value);
+    log::warn!("shouted {}", value);
+    value.to_uppercase()
+}
+
+export_functions!(shout);
+
+fn call() {
+    let input = common::input(&[Term::literal("shout"), Term::literal("woof")]);
+    assert!(String::from_utf8(common::evaluate(evaluate, input.as_bytes())).unwrap().contains("WOOF"));
+}
+
+/// Every record not taken yet.
+fn take_records() -> Vec<(u8, String)> {
+    let mut records = Vec::new();
+    loop {
+        let mut len = 0;
+        let record = unsafe { next_log_record(&mut len) };
+        if record.is_null() {
+            return records;
+        }
+
+        let bytes = unsafe { common::take(record, len) };
+        records.push((bytes[0], String::from_utf8(bytes[1..].to_vec()).unwrap()));
+    }
+}
+
+#[test]
+fn the_host_sets_the_log_level_and_takes_the_records() {
+    call();
+    assert_eq!(log::max_level(), LevelFilter::Off);
+    assert!(take_records().is_empty());
+
+    unsafe { set_log_level(4) };
+    assert_eq!(log::max_level(), LevelFilter::Debug);
+    call();
+    assert_eq!(take_records(), [(4, "host_log: shouting woof".to_owned()), (2, "host_log: shouted woof".to_owned())]);
+    assert!(take_records().is_empty());
+
+    unsafe { set_log_level(2) };
+    call();
+    call();
+    assert_eq!(take_records(), [(2, "host_log: shouted woof".to_owned()), (2, "host_log: shouted woof".to_owned())]);
+
+    unsafe { set_log_level(2) };
+    for _ in 0..2000 {
+        call();
+    }
+    let records = take_records();
+    assert_eq!(records.len(), 1 + 1024);
+    assert_eq!(records[0], (2, "stardog_wasm_guest: dropped 976 log records the host did not take in time".to_owned()));
+    assert_eq!(records[1], (2, "host_log: shouted woof".to_owned()));
+
+    unsafe { set_log_level(0) };
+    call();
+    assert!(take_records().is_empty());
+
+    assert_ne!(unsafe { abi_features() } & stardog_wasm_guest::abi::features::HOST_LOG, 0);
+}
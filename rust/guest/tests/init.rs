@@ -0,0 +1,75 @@
+//! `init` sets up state once for every later call, and may be called again.
+
+mod common;
+
+use serde::Deserialize;
+use stardog_wasm_guest::{export_functions, last_error_message, stardog_function, state, Args, Error, ErrorCode, Init, Term};
+
+extern "C" {
+    #[link_name = "init"]
+    fn init(config: *const u8, config_len: usize) -> u32;
+    #[link_name = "stardog_wasm_abi_features"]
+    fn abi_features() -> u32;
+}
+
+#[derive(Deserialize)]
+struct Config {
+    stop_words: Vec<String>,
+}
+
+struct StopWords {
+    words: Vec<String>,
+}
+
+impl Init for StopWords {
+    type Config = Config;
+
+    fn init(config: Config) -> Result<StopWords, Error> {
+        if config.stop_words.iter().any(String::is_empty) {
+            return Err(Error::failed("stop words cannot be empty"));
+        }
+
+        Ok(StopWords { words: config.stop_words })
+    }
+}
+
+#[stardog_function]
+fn is_stop_word(word: &str) -> Result<bool, Error> {
+    Ok(state::<StopWords>()?.words.iter().any(|stop_word| stop_word == word))
+}
+
+export_functions!(is_stop_word; init = StopWords);
+
+fn configure(config: &str) -> u32 {
+    unsafe { init(config.as_ptr(), config.len()) }
+}
+
+fn call(word: &str) -> Result<bool, Error> {
+    let input = common::input(&[Term::literal(word)]);
+    let args = Args::parse(&input).unwrap();
+    let word: &str = args.require(0)?;
+
+    is_stop_word(word)
+}
+
+#[test]
+fn init_sets_up_and_replaces_the_state() {
+    assert_eq!(call("the").unwrap_err().code, ErrorCode::NotInitialized);
+
+    assert_eq!(configure(r#"{"stop_words": ["the", "a"]}"#), 0);
+    assert_eq!(call("the"), Ok(true));
+    assert_eq!(call("der"), Ok(false));
+
+    assert_eq!(configure(r#"{"stop_words": ["der", "die", "das"]}"#), 0);
+    assert_eq!(call("the"), Ok(false));
+    assert_eq!(call("der"), Ok(true));
+
+    // a rejected config leaves the previous state in place
+    assert_eq!(configure(r#"{"stop_words": [""]}"#), 1);
+    assert!(last_error_message().contains("stop words cannot be empty"));
+    assert_eq!(configure(r#"{"stop_words": 1}"#), 1);
+    assert!(last_error_message().contains("config is not valid"));
+    assert_eq!(call("der"), Ok(true));
+
+    assert_ne!(unsafe { abi_features() } & stardog_wasm_guest::abi::features::INIT, 0);
+}
@@ -0,0 +1,69 @@
+//! Language tags are read in any casing, written canonically and matched as
+//! `langMatches` does, and functions keep them on the strings they return.
+
+mod common;
+
+use common::typed;
+use stardog_wasm_guest::{lang_matches, stardog_function, ErrorCode, Function, LangString, LanguageTag, SelectResults, Term};
+
+fn tag(tag: &str) -> String {
+    LanguageTag::parse(tag).unwrap().to_string()
+}
+
+#[test]
+fn tags_are_written_in_canonical_case() {
+    assert_eq!(tag("EN-us"), "en-US");
+    assert_eq!(tag("zh-hant-tw"), "zh-Hant-TW");
+    assert_eq!(tag("sr-LATN-rs-1996"), "sr-Latn-RS-1996");
+    assert_eq!(tag("es-419"), "es-419");
+    assert_eq!(tag("zh-yue-HK"), "zh-yue-HK");
+    assert_eq!(tag("de-DE-u-CO-phonebk"), "de-DE-u-co-phonebk");
+    assert_eq!(tag("en-a-bbb-X-AB-CD"), "en-a-bbb-x-ab-cd");
+    assert_eq!(tag("X-Whatever"), "x-whatever");
+    assert_eq!(tag("I-KLINGON"), "i-klingon");
+    assert_eq!(tag("en-gb-oed"), "en-GB-oed");
+    assert_eq!(LanguageTag::parse("de-at").unwrap(), LanguageTag::parse("DE-AT").unwrap());
+    assert_eq!(LanguageTag::parse("zh-Hant-TW").unwrap().primary_language(), "zh");
+
+    for malformed in &["", "d", "de-", "-de", "de--at", "123", "de-a", "de-x", "de-a-x-foo", "toolongtag", "de-ß", "en-US-x-toolongsubtag"] {
+        assert!(LanguageTag::parse(malformed).is_none(), "{} parsed", malformed);
+    }
+}
+
+#[test]
+fn ranges_match_as_lang_matches_does() {
+    assert!(lang_matches("de", "de"));
+    assert!(lang_matches("de-AT", "DE"));
+    assert!(lang_matches("de-Latn-AT", "de-latn"));
+    assert!(!lang_matches("deu", "de"));
+    assert!(!lang_matches("de", "de-AT"));
+    assert!(lang_matches("fr", "*"));
+    assert!(!lang_matches("", "*"));
+    assert!(LanguageTag::parse("en-GB").unwrap().matches("en"));
+}
+
+/// Converts a string to upper case, keeping its language tag.
+#[stardog_function]
+fn to_upper(value: LangString) -> LangString {
+    value.map(|value| value.to_uppercase())
+}
+
+fn call<F: Function>(argument: Term<'static>) -> Result<SelectResults, ErrorCode> {
+    common::call::<F>(&[argument]).map_err(|error| error.code)
+}
+
+#[test]
+fn functions_keep_the_language_tag() {
+    let result = call::<to_upper>(Term::lang_literal("straße", "de")).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&Term::lang_literal("STRASSE", "de")));
+
+    let result = call::<to_upper>(Term::lang_literal("colour", "EN-gb")).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&Term::lang_literal("COLOUR", "en-GB")));
+
+    let result = call::<to_upper>(typed("abc", "string")).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&Term::literal("ABC")));
+
+    assert_eq!(call::<to_upper>(Term::lang_literal("abc", "not a tag")).unwrap_err(), ErrorCode::TypeError);
+    assert_eq!(call::<to_upper>(Term::iri("http://example.com/abc")).unwrap_err(), ErrorCode::TypeError);
+    assert_eq!(call::<to_upper>(Term::blank_node("abc")).unwrap_err(), ErrorCode::TypeError);
+}
@@ -0,0 +1,38 @@
+//! With the `nul-terminated` feature modules also speak the version 0
+//! convention: `internalEvaluate` takes and returns NUL-terminated strings.
+//!
+//! Run with `cargo test -p stardog-wasm-guest --features nul-terminated`.
+
+#![cfg(feature = "nul-terminated")]
+
+mod common;
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use stardog_wasm_guest::{abi, export_functions, stardog_function, Term};
+
+extern "C" {
+    #[link_name = "internalEvaluate"]
+    fn internal_evaluate(input: *mut c_char) -> *mut c_char;
+    #[link_name = "stardog_wasm_abi_features"]
+    fn abi_features() -> u32;
+}
+
+#[stardog_function]
+fn to_upper(value: &str) -> String {
+    value.to_uppercase()
+}
+
+export_functions!(to_upper);
+
+#[test]
+fn nul_terminated_input_gives_a_nul_terminated_result() {
+    let input = CString::new(common::input(&[Term::literal("to_upper"), Term::literal("straße")])).unwrap();
+
+    let output = unsafe { internal_evaluate(input.as_ptr() as *mut c_char) };
+    let len = unsafe { CStr::from_ptr(output) }.to_bytes().len();
+    let result: serde_json::Value = serde_json::from_slice(&unsafe { common::take(output as *mut u8, len) }).unwrap();
+    assert_eq!(result["results"]["bindings"][0]["result"]["value"], "STRASSE");
+
+    assert!(abi::supports(unsafe { abi_features() }, abi::features::NUL_TERMINATED));
+}
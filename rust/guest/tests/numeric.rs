@@ -0,0 +1,157 @@
+//! The XSD numeric types read every lexical form, write canonical ones and
+//! promote as SPARQL does.
+
+mod common;
+
+use common::typed;
+use std::convert::TryFrom;
+use stardog_wasm_guest::{
+    canonical_double, canonical_float, parse_double, stardog_function, Decimal, ErrorCode, Function, Integer, IntoResult, Numeric, SelectResults, Term,
+};
+
+fn integer(lexical: &str) -> Integer {
+    Integer::parse(lexical).unwrap()
+}
+
+fn decimal(lexical: &str) -> Decimal {
+    Decimal::parse(lexical).unwrap()
+}
+
+#[test]
+fn integers_have_no_size_limit() {
+    let big = integer("123456789012345678901234567890");
+    assert_eq!((big.clone() * big.clone()).to_string(), "15241578753238836750495351562536198787501905199875019052100");
+    assert_eq!((big.clone() - big.clone() * integer("2")).to_string(), "-123456789012345678901234567890");
+    assert_eq!((integer("18446744073709551615") + integer("1")).to_string(), "18446744073709551616");
+
+    let (quotient, remainder) = (big.clone() * big.clone() + integer("7")).div_rem(&big).unwrap();
+    assert_eq!((quotient, remainder), (big.clone(), integer("7")));
+    let (quotient, remainder) = integer("-7").div_rem(&integer("2")).unwrap();
+    assert_eq!((quotient.to_string(), remainder.to_string()), ("-3".to_owned(), "-1".to_owned()));
+    assert!(integer("1").div_rem(&Integer::zero()).is_none());
+
+    assert!(integer("-99999999999999999999") < integer("-1"));
+    assert!(big > integer("18446744073709551616"));
+    assert_eq!(i64::try_from(&integer("-9223372036854775808")), Ok(i64::MIN));
+    assert!(i64::try_from(&integer("9223372036854775808")).is_err());
+    assert_eq!(Integer::from(u64::MAX).to_string(), "18446744073709551615");
+}
+
+#[test]
+fn lexical_forms_are_read_and_written_canonically() {
+    assert_eq!(integer(" +007 ").to_string(), "7");
+    assert_eq!(integer("-0").to_string(), "0");
+    assert!(Integer::parse("1.0").is_none());
+    assert!(Integer::parse("").is_none());
+
+    assert_eq!(decimal("+1.50").to_string(), "1.5");
+    assert_eq!(decimal("10").to_string(), "10.0");
+    assert_eq!(decimal(".5").to_string(), "0.5");
+    assert_eq!(decimal("-0.000").to_string(), "0.0");
+    assert_eq!(decimal("-120.0100").to_string(), "-120.01");
+    assert!(Decimal::parse("1e5").is_none());
+    assert!(Decimal::parse(".").is_none());
+
+    assert_eq!(canonical_double(150.0), "1.5E2");
+    assert_eq!(canonical_double(1.0), "1.0E0");
+    assert_eq!(canonical_double(-0.0), "-0.0E0");
+    assert_eq!(canonical_double(0.001), "1.0E-3");
+    assert_eq!(canonical_double(f64::NEG_INFINITY), "-INF");
+    assert_eq!(canonical_float(0.1), "1.0E-1");
+    assert_eq!(parse_double("-INF"), Some(f64::NEG_INFINITY));
+    assert!(parse_double("NaN").unwrap().is_nan());
+    assert_eq!(parse_double("1e3"), Some(1000.0));
+    assert_eq!(parse_double("5."), Some(5.0));
+    assert!(parse_double("inf").is_none());
+    assert!(parse_double("1e").is_none());
+    assert!(parse_double("0x10").is_none());
+}
+
+#[test]
+fn derived_integer_types_are_checked_against_their_bounds() {
+    assert!(Numeric::from_term(&typed("127", "byte")).is_some());
+    assert!(Numeric::from_term(&typed("128", "byte")).is_none());
+    assert!(Numeric::from_term(&typed("0", "positiveInteger")).is_none());
+    assert!(Numeric::from_term(&typed("-1", "unsignedLong")).is_none());
+    assert!(Numeric::from_term(&typed("1", "string")).is_none());
+}
+
+#[test]
+fn arithmetic_promotes_to_the_wider_type() {
+    let sum = Numeric::from(integer("1")) + Numeric::from(decimal("2.5"));
+    assert_eq!(sum.into_term(), typed("3.5", "decimal"));
+
+    let quotient = Numeric::from(integer("1")).checked_div(Numeric::from(integer("3"))).unwrap();
+    assert_eq!(quotient.into_term(), typed("0.333333333333333333333333", "decimal"));
+    assert!(Numeric::from(integer("1")).checked_div(Numeric::from(integer("0"))).is_err());
+    assert_eq!(Numeric::from(1.0f64).checked_div(Numeric::from(integer("0"))).unwrap().into_term(), typed("INF", "double"));
+
+    let product = Numeric::from(decimal("0.5")) * Numeric::from(2.0f32);
+    assert_eq!(product.into_term(), typed("1.0E0", "float"));
+
+    assert_eq!(Numeric::from(integer("1")), Numeric::from(1.0f64));
+    assert!(Numeric::from(decimal("0.1")) < Numeric::from(integer("1")));
+    assert!(Numeric::from(f64::NAN).partial_cmp(&Numeric::from(integer("1"))).is_none());
+}
+
+#[test]
+fn rust_numbers_become_typed_literals() {
+    assert_eq!(42u8.into_result(), Ok(typed("42", "integer")));
+    assert_eq!(0.5f64.into_result(), Ok(typed("5.0E-1", "double")));
+    assert_eq!(decimal("2.50").into_result(), Ok(typed("2.5", "decimal")));
+}
+
+/// Multiplies 1 to `n`, however big the result.
+#[stardog_function]
+fn factorial(n: u32) -> Integer {
+    (1..=n).fold(Integer::from(1), |product, i| product * Integer::from(i))
+}
+
+#[stardog_function]
+fn half(value: f64) -> f64 {
+    value / 2.0
+}
+
+fn call<F: Function>(argument: Term<'static>) -> Result<SelectResults, ErrorCode> {
+    common::call::<F>(&[argument]).map_err(|error| error.code)
+}
+
+#[test]
+fn parameters_take_any_numeric_subtype_that_promotes() {
+    let result = call::<factorial>(typed("30", "unsignedShort")).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&typed("265252859812191058636308480000000", "integer")));
+    assert_eq!(call::<factorial>(typed("30.0", "decimal")).unwrap_err(), ErrorCode::TypeError);
+
+    let result = call::<half>(typed("3", "integer")).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&typed("1.5E0", "double")));
+    assert!(call::<half>(typed("3.0", "decimal")).is_ok());
+    assert!(call::<half>(typed("3.0E0", "float")).is_ok());
+    assert!(call::<half>(Term::literal("3")).is_ok());
+}
+
+#[stardog_function]
+fn as_numeric(value: Numeric) -> Numeric {
+    value
+}
+
+#[stardog_function]
+fn as_decimal(value: Decimal) -> Decimal {
+    value
+}
+
+#[test]
+fn special_values_are_only_read_for_float_and_double_parameters() {
+    let result = call::<half>(Term::literal("INF")).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&typed("INF", "double")));
+    assert!(call::<half>(typed("NaN", "float")).is_ok());
+
+    assert_eq!(call::<as_numeric>(Term::literal("INF")).unwrap_err(), ErrorCode::TypeError);
+    assert_eq!(call::<as_numeric>(Term::literal("NaN")).unwrap_err(), ErrorCode::TypeError);
+    assert_eq!(call::<as_numeric>(Term::literal("1e400")).unwrap_err(), ErrorCode::TypeError);
+    assert_eq!(call::<as_numeric>(Term::literal("1.5e0")).unwrap().bindings()[0].get("result"), Some(&typed("1.5E0", "double")));
+    assert!(call::<as_numeric>(typed("-INF", "double")).is_ok());
+
+    assert_eq!(call::<as_decimal>(Term::literal("INF")).unwrap_err(), ErrorCode::TypeError);
+    assert_eq!(call::<as_decimal>(typed("INF", "double")).unwrap_err(), ErrorCode::TypeError);
+    assert_eq!(call::<factorial>(Term::literal("NaN")).unwrap_err(), ErrorCode::TypeError);
+}
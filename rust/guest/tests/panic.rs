@@ -0,0 +1,51 @@
+//! A function that panics traps, but leaves its message and location for the
+//! host to read until the next call.
+
+mod common;
+
+use std::panic;
+use stardog_wasm_guest::{Args, Term};
+
+extern "C" {
+    #[link_name = "last_error"]
+    fn last_error() -> *const u8;
+    #[link_name = "last_error_len"]
+    fn last_error_len() -> usize;
+}
+
+fn divide(args: &Args) -> i64 {
+    let dividend: i64 = args.argument(0).unwrap();
+    let divisor: i64 = args.argument(1).unwrap();
+
+    dividend / divisor
+}
+
+fn divide_by(divisor: i64) -> String {
+    common::input(&[Term::literal("84"), Term::literal(divisor.to_string())])
+}
+
+fn read_last_error() -> String {
+    unsafe { String::from_utf8(std::slice::from_raw_parts(last_error(), last_error_len()).to_vec()).unwrap() }
+}
+
+#[test]
+fn a_panic_is_readable_until_the_next_call() {
+    let input = divide_by(0);
+    let trapped = panic::catch_unwind(|| unsafe {
+        let mut len = 0;
+        stardog_wasm_guest::evaluate(input.as_ptr(), input.len(), &mut len, divide)
+    });
+    assert!(trapped.is_err());
+
+    let last_error = read_last_error();
+    assert!(last_error.contains("attempt to divide by zero"), "{}", last_error);
+    assert!(last_error.contains("tests/panic.rs:"), "{}", last_error);
+
+    let input = divide_by(2);
+    let mut len = 0;
+    let output = unsafe { common::take(stardog_wasm_guest::evaluate(input.as_ptr(), input.len(), &mut len, divide), len) };
+    assert!(String::from_utf8(output).unwrap().contains(r#""value":"42""#));
+
+    assert_eq!(unsafe { last_error_len() }, 0);
+    assert_eq!(read_last_error(), "");
+}
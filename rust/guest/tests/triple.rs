@@ -0,0 +1,115 @@
+//! RDF-star quoted triples are read and written, nested, in both encodings,
+//! and functions can take them apart and build them.
+
+mod common;
+
+use std::borrow::Cow;
+use stardog_wasm_guest::{binary, stardog_function, Args, ErrorCode, Function, LangString, SelectResults, Term, Triple};
+
+const QUOTED: &str = r#"{"type":"triple","value":{"subject":{"type":"triple","value":{"subject":{"type":"uri","value":"http://example.org/alice"},"predicate":{"type":"uri","value":"http://xmlns.com/foaf/0.1/knows"},"object":{"type":"bnode","value":"bob"}}},"predicate":{"type":"uri","value":"http://example.org/certainty"},"object":{"type":"literal","value":"0.9","datatype":"http://www.w3.org/2001/XMLSchema#decimal"}}}"#;
+
+fn statement() -> Term<'static> {
+    let knows = Term::triple(Term::iri("http://example.org/alice"), Term::iri("http://xmlns.com/foaf/0.1/knows"), Term::blank_node("bob"));
+    Term::triple(knows, Term::iri("http://example.org/certainty"), Term::typed_literal("0.9", "http://www.w3.org/2001/XMLSchema#decimal"))
+}
+
+#[test]
+fn quoted_triples_nest_in_json() {
+    let term: Term = serde_json::from_str(QUOTED).unwrap();
+    assert_eq!(term, statement());
+    assert_eq!(serde_json::to_string(&statement()).unwrap(), QUOTED);
+    assert_eq!(
+        statement().to_string(),
+        r#"<< << <http://example.org/alice> <http://xmlns.com/foaf/0.1/knows> _:bob >> <http://example.org/certainty> "0.9"^^<http://www.w3.org/2001/XMLSchema#decimal> >>"#
+    );
+
+    let input = common::input(&[statement()]);
+    let args = Args::parse(&input).unwrap();
+    let subject = &args.term(0).and_then(Term::as_triple).unwrap().subject;
+    assert!(matches!(subject.as_triple().unwrap().subject, Term::Iri(Cow::Borrowed(_))));
+
+    for malformed in &[
+        r#"{"type":"triple","value":"<< >>"}"#,
+        r#"{"type":"uri","value":{"subject":{"type":"bnode","value":"a"},"predicate":{"type":"uri","value":"p"},"object":{"type":"bnode","value":"b"}}}"#,
+        r#"{"type":"triple","value":{"subject":{"type":"bnode","value":"a"},"predicate":{"type":"uri","value":"p"}}}"#,
+    ] {
+        assert!(serde_json::from_str::<Term>(malformed).is_err(), "{} was read", malformed);
+    }
+}
+
+#[test]
+fn quoted_triples_nest_in_the_binary_encoding() {
+    let values = vec![Some(Term::iri("file:///rdf-star.wasm")), Some(statement())];
+    let encoded = binary::encode_arguments(&values);
+    let args = binary::decode_arguments(&encoded).unwrap();
+    assert_eq!(args.term(0), Some(&statement()));
+
+    // a triple whose subject is unbound
+    assert!(binary::decode_arguments(&[1, 0, 0, 0, 6, 0]).is_err());
+
+    let deep = (0..100).fold(Term::iri("urn:x"), |term, _| Term::triple(term, Term::iri("urn:p"), Term::iri("urn:o")));
+    assert!(binary::decode_arguments(&binary::encode_arguments(&[Some(deep)])).is_err());
+}
+
+/// The object of a statement.
+#[stardog_function]
+fn object(#[accept(triple)] statement: &Triple) -> Term<'static> {
+    statement.object.clone().into_owned()
+}
+
+/// The statement that the subject has the object for the predicate.
+#[stardog_function]
+fn quote(subject: Term, predicate: Term, object: Term) -> Triple<'static> {
+    Triple::new(subject, predicate, object).into_owned()
+}
+
+#[stardog_function]
+fn length(value: &str) -> usize {
+    value.len()
+}
+
+#[stardog_function]
+fn shout(value: String) -> String {
+    value.to_uppercase()
+}
+
+#[stardog_function]
+fn to_upper(value: LangString) -> LangString {
+    value.map(|value| value.to_uppercase())
+}
+
+#[stardog_function]
+fn not(value: bool) -> bool {
+    !value
+}
+
+fn call<F: Function>(arguments: &[Term<'static>]) -> Result<SelectResults, ErrorCode> {
+    common::call::<F>(arguments).map_err(|error| error.code)
+}
+
+#[test]
+fn functions_take_and_return_quoted_triples() {
+    let result = call::<object>(&[statement()]).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), statement().as_triple().map(|triple| &triple.object));
+    assert_eq!(call::<object>(&[Term::iri("http://example.org/alice")]).unwrap_err(), ErrorCode::TypeError);
+
+    let parts = statement().as_triple().cloned().unwrap();
+    let result = call::<quote>(&[parts.subject, parts.predicate, parts.object]).unwrap();
+    assert_eq!(result.bindings()[0].get("result"), Some(&statement()));
+    assert_eq!(quote::describe().returns, "triple");
+}
+
+#[test]
+fn quoted_triples_are_not_strings() {
+    assert_eq!(statement().value(), None);
+    assert_eq!(Term::literal("woof").value(), Some("woof"));
+
+    assert_eq!(call::<length>(&[statement()]).unwrap_err(), ErrorCode::TypeError);
+    assert_eq!(call::<shout>(&[statement()]).unwrap_err(), ErrorCode::TypeError);
+    assert_eq!(call::<to_upper>(&[statement()]).unwrap_err(), ErrorCode::TypeError);
+    assert_eq!(call::<not>(&[statement()]).unwrap_err(), ErrorCode::TypeError);
+    assert!(call::<length>(&[Term::literal("woof")]).is_ok());
+
+    let input = common::input(&[statement()]);
+    assert_eq!(Args::parse(&input).unwrap().value(0), None);
+}
@@ -1,34 +1,9 @@
-use std::ffi::{CStr, CString};
-use std::mem;
-use std::os::raw::{c_char, c_void};
-use serde_json::{Value, json};
+use stardog_wasm_guest::{export_functions, stardog_function, LangString};
 
-#[no_mangle]
-pub extern fn allocate(size: usize) -> *mut c_void {
-    let mut buffer = Vec::with_capacity(size);
-    let pointer = buffer.as_mut_ptr();
-    mem::forget(buffer);
-
-    pointer as *mut c_void
-}
-
-#[no_mangle]
-pub extern fn deallocate(pointer: *mut c_void, capacity: usize) {
-    unsafe {
-        let _ = Vec::from_raw_parts(pointer, 0, capacity);
-    }
+/// Converts a string to upper case, keeping its language tag.
+#[stardog_function]
+fn to_upper(value: LangString) -> LangString {
+    value.map(|value| value.to_uppercase())
 }
 
-#[no_mangle]
-pub extern fn internalEvaluate(subject: *mut c_char) -> *mut c_char {
-    let subject = unsafe { CStr::from_ptr(subject).to_str().unwrap() };
-    
-    let mut output = b"".to_vec();
-    let v: Value = serde_json::from_str(subject).unwrap();
-    let result = v["results"]["bindings"][1]["value[1]"]["value"].as_str().unwrap().to_uppercase();
-    output.extend(json!({
-      "head": {"vars":["result"]}, "results":{"bindings":[{"result":{"type":"literal","value": result}}]}
-    }).to_string().bytes());
-
-    unsafe { CString::from_vec_unchecked(output) }.into_raw()
-}
+export_functions!(to_upper);
@@ -0,0 +1,107 @@
+use std::vec;
+use stardog_wasm_guest::{export_functions, Args, Date, Duration, Error, ErrorCode, TableFunction};
+
+/// The whitespace separated tokens of a string, with their positions.
+struct Tokens {
+    tokens: vec::IntoIter<(String, usize)>,
+}
+
+impl TableFunction for Tokens {
+    const NAME: &'static str = "tokens";
+    const DESCRIPTION: &'static str = "The whitespace separated tokens of a string, with their positions.";
+    const VARS: &'static [&'static str] = &["token", "position"];
+    type Row = (String, usize);
+
+    fn open(args: &Args) -> Result<Tokens, Error> {
+        let text: &str = args.require(0)?;
+        let tokens: Vec<_> = text.split_whitespace().map(str::to_owned).zip(0..).collect();
+
+        Ok(Tokens { tokens: tokens.into_iter() })
+    }
+
+    fn next(&mut self) -> Option<Result<(String, usize), Error>> {
+        self.tokens.next().map(Ok)
+    }
+}
+
+/// Every `xsd:date` from the first date to the second, inclusive, optionally
+/// every so many days.
+struct DateRange {
+    next: Option<Date>,
+    last: Date,
+    step: Duration,
+}
+
+impl TableFunction for DateRange {
+    const NAME: &'static str = "date_range";
+    const DESCRIPTION: &'static str = "Every date from the first to the second, inclusive, optionally every so many days.";
+    const VARS: &'static [&'static str] = &["date"];
+    type Row = Date;
+
+    fn open(args: &Args) -> Result<DateRange, Error> {
+        let step = args.require::<Option<i64>>(2)?.unwrap_or(1);
+        if step < 1 {
+            return Err(Error::new(ErrorCode::TypeError, "the step must be at least one day"));
+        }
+
+        let (first, last): (Date, Date) = (args.require(0)?, args.require(1)?);
+        // a date with a timezone and one without may not be comparable at all
+        if first.timezone().is_some() != last.timezone().is_some() {
+            return Err(Error::new(ErrorCode::TypeError, "either both dates or neither must have a timezone"));
+        }
+
+        Ok(DateRange { next: Some(first), last, step: Duration::from_days(step) })
+    }
+
+    fn next(&mut self) -> Option<Result<Date, Error>> {
+        let date = self.next?;
+        if date > self.last {
+            return None;
+        }
+
+        self.next = date.checked_add(self.step);
+
+        Some(Ok(date))
+    }
+}
+
+export_functions!(; tables = Tokens, DateRange);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(arguments: &[&str]) -> Result<DateRange, Error> {
+        let arguments: Vec<String> =
+            arguments.iter().enumerate().map(|(i, argument)| format!(r#"{{"value[{}]":{{"type":"literal","value":"{}"}}}}"#, i + 1, argument)).collect();
+        let input = format!(
+            r#"{{"head":{{"vars":[]}},"results":{{"bindings":[{{"value[0]":{{"type":"uri","value":"file:///tables.wasm"}}}},{}]}}}}"#,
+            arguments.join(",")
+        );
+
+        DateRange::open(&Args::parse(&input).unwrap())
+    }
+
+    fn dates(mut range: DateRange) -> Vec<String> {
+        std::iter::from_fn(|| range.next()).map(|date| date.unwrap().to_string()).collect()
+    }
+
+    #[test]
+    fn the_step_defaults_to_one_day() {
+        assert_eq!(dates(open(&["2021-01-30", "2021-02-01"]).unwrap()), ["2021-01-30", "2021-01-31", "2021-02-01"]);
+        assert_eq!(dates(open(&["2021-01-30", "2021-02-03", "2"]).unwrap()), ["2021-01-30", "2021-02-01", "2021-02-03"]);
+    }
+
+    #[test]
+    fn a_step_that_is_not_an_integer_is_a_type_error() {
+        assert_eq!(open(&["2021-01-30", "2021-02-01", "abc"]).err().map(|error| error.code), Some(ErrorCode::TypeError));
+        assert_eq!(open(&["2021-01-30", "2021-02-01", "0"]).err().map(|error| error.code), Some(ErrorCode::TypeError));
+    }
+
+    #[test]
+    fn a_timezone_on_only_one_date_is_a_type_error() {
+        assert_eq!(open(&["2021-01-30Z", "2021-02-01"]).err().map(|error| error.code), Some(ErrorCode::TypeError));
+        assert_eq!(open(&["2021-01-30", "2021-02-01+01:00"]).err().map(|error| error.code), Some(ErrorCode::TypeError));
+        assert_eq!(dates(open(&["2021-01-31+01:00", "2021-02-01Z"]).unwrap()), ["2021-01-31+01:00", "2021-02-01+01:00"]);
+    }
+}
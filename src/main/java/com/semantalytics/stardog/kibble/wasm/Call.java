@@ -8,10 +8,12 @@ import com.complexible.stardog.plan.filter.ExpressionVisitor;
 import com.complexible.stardog.plan.filter.ValueSolution;
 import com.complexible.stardog.plan.filter.expr.ValueOrError;
 import com.complexible.stardog.plan.filter.functions.UserDefinedFunction;
+import com.fasterxml.jackson.databind.JsonNode;
+import com.fasterxml.jackson.databind.ObjectMapper;
 import com.google.common.cache.*;
 import com.google.common.collect.Lists;
+import com.stardog.stark.Literal;
 import com.stardog.stark.Value;
-import com.stardog.stark.query.Binding;
 import com.stardog.stark.query.BindingSet;
 import com.stardog.stark.query.BindingSets;
 import com.stardog.stark.query.SelectQueryResult;
@@ -22,6 +24,9 @@ import com.stardog.stark.query.io.QueryResultWriters;
 import org.apache.commons.io.IOUtils;
 import org.wasmer.Instance;
 import org.wasmer.Memory;
+import org.wasmer.exports.Function;
+import org.slf4j.Logger;
+import org.slf4j.LoggerFactory;
 
 import java.io.ByteArrayInputStream;
 import java.io.ByteArrayOutputStream;
@@ -30,6 +35,7 @@ import java.net.MalformedURLException;
 import java.net.URL;
 import java.net.URLConnection;
 import java.nio.ByteBuffer;
+import java.nio.charset.StandardCharsets;
 import java.util.Arrays;
 import java.util.List;
 import java.util.Optional;
@@ -40,6 +46,22 @@ import static java.util.stream.Collectors.*;
 
 public class Call extends AbstractExpression implements UserDefinedFunction {
 
+    private static final Logger LOGGER = LoggerFactory.getLogger(Call.class);
+
+    private static final ObjectMapper OBJECT_MAPPER = new ObjectMapper();
+
+    /**
+     * The newest calling convention this host speaks. See `stardog_wasm_guest::abi` for what each version means;
+     * modules without a `stardog_wasm_abi_version` export are version 0.
+     */
+    static final int ABI_VERSION = 1;
+
+    /**
+     * Bit of `stardog_wasm_abi_features` set by modules that export several functions: the second argument of
+     * `wasm:call` names the one to call rather than being its first argument.
+     */
+    static final int FEATURE_DISPATCH = 1 << 2;
+
     private int memorySize = 1;
 
     /* NOTES
@@ -64,7 +86,9 @@ public class Call extends AbstractExpression implements UserDefinedFunction {
                                                                 .build(new CacheLoader<URL, Instance>() {
         @Override
         public Instance load(URL url) throws IOException {
-            return new Instance(getWasm(url));
+            final Instance instance = new Instance(getWasm(url));
+            setLogLevel(instance);
+            return instance;
         }
     });
 
@@ -112,52 +136,43 @@ public class Call extends AbstractExpression implements UserDefinedFunction {
                     return ValueOrError.Error;
                 }
 
-                Integer input_pointer = (Integer) instance.exports.getFunction("allocate").apply(byteArrayOutputStream.toByteArray().length)[0];
-
-                Memory memory = instance.exports.getMemory("memory");
-                ByteBuffer memoryBuffer = memory.buffer();
-                byte[] input = byteArrayOutputStream.toByteArray();
-                int pages = (int)Math.ceil(input.length / 64.0);
-                if(pages > memorySize) {
-                    memory.grow(pages - memorySize);
-                    memorySize = pages;
+                final int abiVersion = abiVersion(instance);
+                if (abiVersion > ABI_VERSION) {
+                    LOGGER.warn("{} needs wasm ABI version {} but only up to {} is supported", wasmUrl, abiVersion, ABI_VERSION);
+                    return ValueOrError.Error;
                 }
-                memoryBuffer.position(input_pointer);
-                memoryBuffer.put(input);
 
-                final Integer output_pointer = (Integer) instance.exports.getFunction("internalEvaluate").apply(input_pointer)[0];
+                final int abiFeatures = abiFeatures(instance);
+                if ((abiFeatures & FEATURE_DISPATCH) != 0 && (values.length < 2 || !(values[1] instanceof Literal))) {
+                    LOGGER.warn("{} exports several functions, so the second argument has to name one", wasmUrl);
+                    return ValueOrError.Error;
+                }
 
-                final SelectQueryResult selectQueryResult;
+                final byte[] output;
                 try {
-                    selectQueryResult = QueryResultParsers.readSelect(new ByteArrayInputStream(readResult(memory, output_pointer).toByteArray()), QueryResultFormats.JSON);
-                } catch (IOException e) {
+                    output = invoke(instance, abiVersion, byteArrayOutputStream.toByteArray());
+                    forwardLog(instance, wasmUrl);
+                } catch (RuntimeException e) {
+                    forwardLog(instance, wasmUrl);
+                    LOGGER.warn("{} trapped: {}", wasmUrl, readLastError(instance).orElse(e.getMessage()));
+                    instanceCache.invalidate(wasmUrl);
                     return ValueOrError.Error;
                 }
 
-                final Optional<BindingSet> bs = selectQueryResult.stream().findFirst();
+                final Optional<String> error = errorMessage(output);
+                if (error.isPresent()) {
+                    LOGGER.warn("{} failed: {}", wasmUrl, error.get());
+                    return ValueOrError.Error;
+                }
 
+                final SelectQueryResult selectQueryResult;
                 try {
-                    if (bs.isPresent()) {
-                        if (bs.get().size() > 1) {
-                            final MappingDictionary mappingDictionary = valueSolution.getDictionary();
-                            final long[] ids = bs.get().stream().map(b -> b.get()).mapToLong(v -> mappingDictionary.add(v)).toArray();
-                            return ValueOrError.General.of(new ArrayLiteral(ids));
-                        } else if (bs.get().size() == 1) {
-                            final Optional<Binding> firstVar = bs.get().stream().findFirst();
-                            if (firstVar.isPresent()) {
-                                return ValueOrError.General.of(firstVar.get().value());
-                            } else {
-                                return ValueOrError.Error;
-                            }
-                        } else {
-                            return ValueOrError.Error;
-                        }
-                    } else {
-                        return ValueOrError.Error;
-                    }
-                } finally {
-                    instance.exports.getFunction("deallocate").apply(input_pointer, byteArrayOutputStream.toByteArray().length);
+                    selectQueryResult = QueryResultParsers.readSelect(new ByteArrayInputStream(output), QueryResultFormats.JSON);
+                } catch (IOException e) {
+                    return ValueOrError.Error;
                 }
+
+                return resultValue(selectQueryResult, valueSolution.getDictionary());
             } else {
                 return ValueOrError.Error;
             }
@@ -166,6 +181,51 @@ public class Call extends AbstractExpression implements UserDefinedFunction {
         }
     }
 
+    /**
+     * The value of a module's result: its only value, or every value of every row, row by row in head order, as an
+     * array. Results without variables are errors, and so are results that bind nothing, which is how modules leave
+     * the value unbound (for `None` or an empty `Vec`).
+     */
+    static ValueOrError resultValue(final SelectQueryResult selectQueryResult, final MappingDictionary mappingDictionary) {
+        final List<String> resultVars = selectQueryResult.variables();
+        if (resultVars.isEmpty()) {
+            // modules report errors as a result without variables
+            return ValueOrError.Error;
+        }
+
+        final List<Value> resultValues = selectQueryResult.stream()
+                .flatMap(bs -> resultVars.stream().map(bs::value).filter(Optional::isPresent).map(Optional::get))
+                .collect(toList());
+
+        if (resultValues.isEmpty()) {
+            return ValueOrError.Error;
+        } else if (resultValues.size() == 1) {
+            return ValueOrError.General.of(resultValues.get(0));
+        } else {
+            final long[] ids = resultValues.stream().mapToLong(mappingDictionary::add).toArray();
+            return ValueOrError.General.of(new ArrayLiteral(ids));
+        }
+    }
+
+    /**
+     * The `error` member modules add to a result that failed, as its code followed by its message. SPARQL JSON has
+     * no place for it, so it is read from the output before the output is parsed as a result.
+     */
+    static Optional<String> errorMessage(final byte[] output) {
+        final JsonNode error;
+        try {
+            error = OBJECT_MAPPER.readTree(output).path("error");
+        } catch (IOException e) {
+            return Optional.empty();
+        }
+
+        if (!error.isObject()) {
+            return Optional.empty();
+        }
+
+        return Optional.of(String.format("%s: %s", error.path("code").asText("failed"), error.path("message").asText("")));
+    }
+
     private byte[] getWasm(final URL wasmUrl) throws IOException {
         final ByteArrayOutputStream baos;
 
@@ -179,6 +239,75 @@ public class Call extends AbstractExpression implements UserDefinedFunction {
         return baos.toByteArray();
     }
 
+    static int abiVersion(final Instance instance) {
+        final Function abiVersion = instance.exports.getFunction("stardog_wasm_abi_version");
+
+        return abiVersion == null ? 0 : (Integer) abiVersion.apply()[0];
+    }
+
+    /**
+     * Modules that predate `stardog_wasm_abi_features` have none of the optional features.
+     */
+    static int abiFeatures(final Instance instance) {
+        final Function abiFeatures = instance.exports.getFunction("stardog_wasm_abi_features");
+
+        return abiFeatures == null ? 0 : (Integer) abiFeatures.apply()[0];
+    }
+
+    /**
+     * Since ABI version 1 modules export `evaluate`, which takes the length of the input and reports the length of
+     * the result through an out-parameter. Older modules only export `internalEvaluate`, which takes and returns
+     * NUL-terminated strings.
+     */
+    private byte[] invoke(final Instance instance, final int abiVersion, final byte[] input) {
+        final Function allocate = instance.exports.getFunction("allocate");
+        final Function deallocate = instance.exports.getFunction("deallocate");
+        final Memory memory = instance.exports.getMemory("memory");
+
+        if (abiVersion >= 1) {
+            final Function evaluate = instance.exports.getFunction("evaluate");
+            final Integer input_pointer = (Integer) allocate.apply(input.length)[0];
+            final Integer output_length_pointer = (Integer) allocate.apply(4)[0];
+            writeInput(memory, input_pointer, input);
+
+            final Integer output_pointer = (Integer) evaluate.apply(input_pointer, input.length, output_length_pointer)[0];
+            final ByteBuffer memoryBuffer = memory.buffer();
+            final byte[] output = new byte[memoryBuffer.getInt(output_length_pointer)];
+            memoryBuffer.position(output_pointer);
+            memoryBuffer.get(output);
+
+            freeResult(instance, output_pointer, output.length);
+            deallocate.apply(output_length_pointer, 4);
+            deallocate.apply(input_pointer, input.length);
+            resetHeap(instance);
+
+            return output;
+        } else {
+            final byte[] terminated = Arrays.copyOf(input, input.length + 1);
+            final Integer input_pointer = (Integer) allocate.apply(terminated.length)[0];
+            writeInput(memory, input_pointer, terminated);
+
+            final Integer output_pointer = (Integer) instance.exports.getFunction("internalEvaluate").apply(input_pointer)[0];
+            final byte[] output = readResult(memory, output_pointer).toByteArray();
+
+            freeResult(instance, output_pointer, output.length);
+            deallocate.apply(input_pointer, terminated.length);
+
+            return output;
+        }
+    }
+
+    private void writeInput(final Memory memory, final Integer input_pointer, final byte[] input) {
+        int pages = (int)Math.ceil(input.length / 64.0);
+        if(pages > memorySize) {
+            memory.grow(pages - memorySize);
+            memorySize = pages;
+        }
+        ByteBuffer memoryBuffer = memory.buffer();
+        memoryBuffer.position(input_pointer);
+        memoryBuffer.put(input);
+    }
+
     private ByteArrayOutputStream readResult(final Memory memory, final Integer output_pointer) {
         ByteArrayOutputStream baos = new ByteArrayOutputStream();
         StringBuilder output = new StringBuilder();
@@ -200,6 +329,101 @@ public class Call extends AbstractExpression implements UserDefinedFunction {
         return baos;
     }
 
+    /**
+     * Results belong to the host once returned. Modules built before `free_result` existed have no way to release
+     * them, so they are left to leak as before.
+     */
+    static void freeResult(final Instance instance, final Integer output_pointer, final int length) {
+        final Function freeResult = instance.exports.getFunction("free_result");
+
+        if (freeResult != null) {
+            freeResult.apply(output_pointer, length);
+        }
+    }
+
+    /**
+     * Modules built with the `arena` feature release everything a call allocated here rather than at the start of
+     * the next call, so a cached instance does not hold on to the memory of its largest call in the meantime.
+     */
+    static void resetHeap(final Instance instance) {
+        final Function resetHeap = instance.exports.getFunction("reset_heap");
+
+        if (resetHeap != null) {
+            resetHeap.apply();
+        }
+    }
+
+    /**
+     * Modules built with the `host-log` feature log nothing until told which levels to keep, 1 (error) to 5 (trace).
+     */
+    static void setLogLevel(final Instance instance) {
+        final Function setLogLevel = instance.exports.getFunction("set_log_level");
+
+        if (setLogLevel != null) {
+            setLogLevel.apply(LOGGER.isTraceEnabled() ? 5
+                              : LOGGER.isDebugEnabled() ? 4
+                              : LOGGER.isInfoEnabled() ? 3
+                              : LOGGER.isWarnEnabled() ? 2
+                              : LOGGER.isErrorEnabled() ? 1
+                              : 0);
+        }
+    }
+
+    /**
+     * Passes on what the last call logged. Each record is its level as one byte followed by the UTF-8 message, and
+     * a null pointer means there are none left. Records the module had to drop are reported in a warning record.
+     */
+    static void forwardLog(final Instance instance, final URL wasmUrl) {
+        final Function nextLogRecord = instance.exports.getFunction("next_log_record");
+
+        if (nextLogRecord == null) {
+            return;
+        }
+
+        final Function allocate = instance.exports.getFunction("allocate");
+        final Function deallocate = instance.exports.getFunction("deallocate");
+        final Integer output_length_pointer = (Integer) allocate.apply(4)[0];
+
+        for (Integer record_pointer = (Integer) nextLogRecord.apply(output_length_pointer)[0];
+             record_pointer != 0;
+             record_pointer = (Integer) nextLogRecord.apply(output_length_pointer)[0]) {
+            final ByteBuffer memoryBuffer = instance.exports.getMemory("memory").buffer();
+            final byte[] record = new byte[memoryBuffer.getInt(output_length_pointer)];
+            memoryBuffer.position(record_pointer);
+            memoryBuffer.get(record);
+            freeResult(instance, record_pointer, record.length);
+
+            final String message = new String(record, 1, record.length - 1, StandardCharsets.UTF_8);
+            switch (record[0]) {
+                case 1: LOGGER.error("{}: {}", wasmUrl, message); break;
+                case 2: LOGGER.warn("{}: {}", wasmUrl, message); break;
+                case 3: LOGGER.info("{}: {}", wasmUrl, message); break;
+                case 4: LOGGER.debug("{}: {}", wasmUrl, message); break;
+                default: LOGGER.trace("{}: {}", wasmUrl, message); break;
+            }
+        }
+
+        deallocate.apply(output_length_pointer, 4);
+    }
+
+    private Optional<String> readLastError(final Instance instance) {
+        final Function lastError = instance.exports.getFunction("last_error");
+        final Function lastErrorLen = instance.exports.getFunction("last_error_len");
+
+        if (lastError == null || lastErrorLen == null) {
+            return Optional.empty();
+        }
+
+        final Integer pointer = (Integer) lastError.apply()[0];
+        final Integer length = (Integer) lastErrorLen.apply()[0];
+        final byte[] bytes = new byte[length];
+        final ByteBuffer memoryBuffer = instance.exports.getMemory("memory").buffer();
+        memoryBuffer.position(pointer);
+        memoryBuffer.get(bytes);
+
+        return Optional.of(new String(bytes, StandardCharsets.UTF_8));
+    }
+
     @Override
     public String getName() {
         return WasmVocabulary.call.toString();
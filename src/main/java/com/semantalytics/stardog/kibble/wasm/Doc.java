@@ -32,6 +32,7 @@ import java.net.MalformedURLException;
 import java.net.URL;
 import java.net.URLConnection;
 import java.nio.ByteBuffer;
+import java.nio.charset.StandardCharsets;
 import java.util.Arrays;
 import java.util.List;
 import java.util.Optional;
@@ -99,17 +100,43 @@ public class Doc extends AbstractExpression implements UserDefinedFunction {
                 return ValueOrError.Error;
             }
 
+            Memory memory = instance.exports.getMemory("memory");
+
+            if (instance.exports.getFunction("describe") != null) {
+                return ValueOrError.General.of(Values.literal(describe(instance, memory)));
+            }
+
             final Integer output_pointer = (Integer) instance.exports.getFunction("doc").apply()[0];
 
-            Memory memory = instance.exports.getMemory("memory");
+            final ByteArrayOutputStream output = readResult(memory, output_pointer);
+            Call.freeResult(instance, output_pointer, output.size());
 
-            return ValueOrError.General.of(Values.literal(readResult(memory, output_pointer).toString()));
+            return ValueOrError.General.of(Values.literal(output.toString()));
 
         } else {
             return ValueOrError.Error;
         }
     }
 
+    /**
+     * Modules built with stardog-wasm-guest describe themselves as JSON generated from their function signatures,
+     * returned the same way `evaluate` returns results.
+     */
+    private String describe(final Instance instance, final Memory memory) {
+        final Integer output_length_pointer = (Integer) instance.exports.getFunction("allocate").apply(4)[0];
+        final Integer output_pointer = (Integer) instance.exports.getFunction("describe").apply(output_length_pointer)[0];
+
+        final ByteBuffer memoryBuffer = memory.buffer();
+        final byte[] output = new byte[memoryBuffer.getInt(output_length_pointer)];
+        memoryBuffer.position(output_pointer);
+        memoryBuffer.get(output);
+
+        Call.freeResult(instance, output_pointer, output.length);
+        instance.exports.getFunction("deallocate").apply(output_length_pointer, 4);
+
+        return new String(output, StandardCharsets.UTF_8);
+    }
+
     private byte[] getWasm(final URL wasmUrl) throws IOException {
         final ByteArrayOutputStream baos;
 
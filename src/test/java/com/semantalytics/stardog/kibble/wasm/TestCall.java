@@ -7,6 +7,7 @@ import com.stardog.stark.query.BindingSet;
 import com.stardog.stark.query.SelectQueryResult;
 import org.junit.Test;
 
+import java.nio.charset.StandardCharsets;
 import java.util.Optional;
 
 import static com.complexible.stardog.plan.filter.functions.AbstractFunction.assertStringLiteral;
@@ -35,4 +36,13 @@ public class TestCall extends AbstractStardogTest {
             }
     }
 
+    @Test
+    public void testErrorMessage() {
+
+        final String anOutput = "{\"head\":{\"vars\":[]},\"results\":{\"bindings\":[]},\"error\":{\"code\":\"missing-argument\",\"message\":\"value[2] is missing\"}}";
+
+        assertThat(Call.errorMessage(anOutput.getBytes(StandardCharsets.UTF_8))).contains("missing-argument: value[2] is missing");
+        assertThat(Call.errorMessage("{\"head\":{\"vars\":[\"result\"]},\"results\":{\"bindings\":[]}}".getBytes(StandardCharsets.UTF_8))).isEmpty();
+    }
+
 }
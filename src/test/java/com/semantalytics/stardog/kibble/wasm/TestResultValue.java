@@ -0,0 +1,47 @@
+package com.semantalytics.stardog.kibble.wasm;
+
+import com.complexible.stardog.plan.filter.expr.ValueOrError;
+import com.stardog.stark.Literal;
+import com.stardog.stark.query.io.QueryResultFormats;
+import com.stardog.stark.query.io.QueryResultParsers;
+import org.junit.Test;
+
+import java.io.ByteArrayInputStream;
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+
+import static org.assertj.core.api.Assertions.assertThat;
+
+public class TestResultValue {
+
+    private static ValueOrError resultValue(final String output) throws IOException {
+        return Call.resultValue(QueryResultParsers.readSelect(new ByteArrayInputStream(output.getBytes(StandardCharsets.UTF_8)), QueryResultFormats.JSON), null);
+    }
+
+    @Test
+    public void testSingleValue() throws IOException {
+
+        final ValueOrError aValue = resultValue("{\"head\":{\"vars\":[\"result\"]},\"results\":{\"bindings\":[{\"result\":{\"type\":\"literal\",\"value\":\"WOOF\"}}]}}");
+
+        assertThat(aValue.isError()).isFalse();
+        assertThat(((Literal) aValue.value()).label()).isEqualTo("WOOF");
+    }
+
+    @Test
+    public void testNoneIsUnbound() throws IOException {
+
+        assertThat(resultValue("{\"head\":{\"vars\":[\"result\"]},\"results\":{\"bindings\":[{}]}}").isError()).isTrue();
+    }
+
+    @Test
+    public void testEmptyVecIsUnbound() throws IOException {
+
+        assertThat(resultValue("{\"head\":{\"vars\":[\"result\"]},\"results\":{\"bindings\":[]}}").isError()).isTrue();
+    }
+
+    @Test
+    public void testErrorEnvelope() throws IOException {
+
+        assertThat(resultValue("{\"head\":{\"vars\":[]},\"results\":{\"bindings\":[]},\"error\":{\"code\":\"type-error\",\"message\":\"woof\"}}").isError()).isTrue();
+    }
+}
@@ -10,6 +10,7 @@ import com.complexible.stardog.api.admin.AdminConnection;
 import com.complexible.stardog.api.admin.AdminConnectionConfiguration;
 import com.google.common.io.Files;
 import com.semantalytics.stardog.kibble.wasm.TestCall;
+import com.semantalytics.stardog.kibble.wasm.TestResultValue;
 import junit.framework.TestCase;
 import org.junit.AfterClass;
 import org.junit.BeforeClass;
@@ -24,6 +25,7 @@ import java.net.InetSocketAddress;
 @RunWith(Suite.class)
 @SuiteClasses({
     TestCall.class,
+    TestResultValue.class,
 })
 
 public class WasmTestSuite extends TestCase {